@@ -0,0 +1,108 @@
+use demo_integrity::*;
+use hc_prefix_index::PrefixIndex;
+use hdk::prelude::*;
+
+/// Search index A with the traversal hc_prefix_index used before results were fetched level by level,
+/// to compare against in benchmarks
+///
+/// Returns the results, along with the number of get_links calls made, including those of path.exists().
+#[hdk_extern]
+pub fn search_index_a_baseline(
+    input: crate::SearchIndexInput,
+) -> ExternResult<(Vec<String>, usize)> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    let path = index
+        .make_result_path(input.query, None)?
+        .typed(index.link_type)?;
+
+    let mut get_links_calls = 0;
+    let results =
+        baseline_get_results_from_path(path, input.limit, vec![], vec![], &mut get_links_calls)?;
+
+    Ok((
+        results
+            .into_iter()
+            .filter_map(|path| path.leaf().and_then(|c| String::try_from(c).ok()))
+            .collect(),
+        get_links_calls,
+    ))
+}
+
+/// Depth-first search passing the visited paths and results by value, cloning them for every child
+fn baseline_get_results_from_path(
+    path: TypedPath,
+    limit: usize,
+    mut visited: Vec<TypedPath>,
+    mut results: Vec<TypedPath>,
+    get_links_calls: &mut usize,
+) -> ExternResult<Vec<TypedPath>> {
+    visited.push(path.clone());
+
+    let children = baseline_children_paths(&path, get_links_calls)?;
+    if children.is_empty() {
+        *get_links_calls += 1;
+        if path.exists()? && !results.contains(&path) && results.len() < limit {
+            results.push(path.clone());
+        }
+    } else {
+        for child in children.into_iter() {
+            let grandchildren = baseline_get_results_from_path(
+                child,
+                limit,
+                visited.clone(),
+                results.clone(),
+                get_links_calls,
+            )
+            .unwrap_or_default();
+
+            for grandchild in grandchildren.into_iter() {
+                *get_links_calls += 1;
+                if grandchild.exists()? && !results.contains(&grandchild) && results.len() < limit {
+                    results.push(grandchild);
+                }
+            }
+        }
+    }
+
+    match path.parent() {
+        Some(parent) if !visited.contains(&parent) && !parent.is_root() => {
+            baseline_get_results_from_path(parent, limit, visited, results, get_links_calls)
+        }
+        _ => Ok(results),
+    }
+}
+
+fn baseline_children_paths(
+    path: &TypedPath,
+    get_links_calls: &mut usize,
+) -> ExternResult<Vec<TypedPath>> {
+    *get_links_calls += 1;
+    let mut links = get_links(
+        GetLinksInputBuilder::try_new(
+            path.path_entry_hash()?,
+            LinkTypeFilter::single_type(path.link_type.zome_index, path.link_type.zome_type),
+        )?
+        .build(),
+    )?;
+    links.sort_unstable_by(|a, b| a.tag.cmp(&b.tag));
+    links.dedup_by(|a, b| a.tag.eq(&b.tag));
+
+    // Links to the hashes attached to results aren't tagged with a Component
+    Ok(links
+        .into_iter()
+        .filter_map(|link| {
+            Component::try_from(SerializedBytes::from(UnsafeBytes::from(link.tag.0))).ok()
+        })
+        .map(|component| {
+            let mut child = path.path.clone();
+            child.append_component(component);
+            child.into_typed(path.link_type)
+        })
+        .collect())
+}
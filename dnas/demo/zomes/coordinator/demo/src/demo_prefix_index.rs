@@ -1,4 +1,7 @@
 use demo_integrity::*;
+use hc_prefix_index::normalize::NormalizationStep;
+use hc_prefix_index::tokenize::{Tokenizer, WordBoundary};
+use hc_prefix_index::types::{BrowseNode, Cursor, Page, SearchOptions, SearchResult, SearchStats};
 use hc_prefix_index::PrefixIndex;
 use hdk::prelude::*;
 
@@ -16,6 +19,22 @@ pub fn add_to_index_a(text: String) -> ExternResult<()> {
     Ok(())
 }
 
+#[hdk_extern]
+pub fn add_many_to_index_a(texts: Vec<String>) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    for text in texts.into_iter() {
+        index.add_result(text)?;
+    }
+
+    Ok(())
+}
+
 #[hdk_extern]
 pub fn remove_from_index_a(text: String) -> ExternResult<()> {
     let index = PrefixIndex::new(
@@ -47,6 +66,239 @@ pub fn search_index_a(input: SearchIndexInput) -> ExternResult<Vec<String>> {
     index.get_results(input.query, input.limit)
 }
 
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct SearchIndexWithOptionsInput {
+    pub query: String,
+    pub options: SearchOptions,
+}
+#[hdk_extern]
+pub fn search_index_a_with_options(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_results_with_options(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn search_index_a_ranked(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<SearchResult>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.search(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn search_index_a_with_stats(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<(Vec<String>, SearchStats)> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_results_with_stats(input.query, input.options)
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct SearchIndexPageInput {
+    pub query: String,
+    pub limit: usize,
+    pub cursor: Option<Cursor>,
+}
+#[hdk_extern]
+pub fn search_index_a_page(input: SearchIndexPageInput) -> ExternResult<Page> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_results_page(input.query, input.limit, input.cursor)
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct SearchIndexFuzzyInput {
+    pub query: String,
+    pub max_edits: usize,
+    pub limit: usize,
+}
+#[hdk_extern]
+pub fn search_index_a_fuzzy(input: SearchIndexFuzzyInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_results_fuzzy(input.query, input.max_edits, input.limit)
+}
+
+#[hdk_extern]
+pub fn count_index_a(query: String) -> ExternResult<usize> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.count_results(query)
+}
+
+#[hdk_extern]
+pub fn count_index_a_by_child(query: String) -> ExternResult<Vec<(String, usize)>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.count_results_by_child(query)
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct EstimateCountInput {
+    pub query: String,
+    pub levels: usize,
+}
+#[hdk_extern]
+pub fn estimate_count_index_a(input: EstimateCountInput) -> ExternResult<usize> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.estimate_count(input.query, input.levels)
+}
+
+#[hdk_extern]
+pub fn browse_index_a(prefix: String) -> ExternResult<Vec<BrowseNode>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.browse(prefix)
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct HashResultInput {
+    pub text: String,
+    pub hash: AnyLinkableHash,
+}
+#[hdk_extern]
+pub fn add_hash_to_index_a(input: HashResultInput) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.add_result_for_hash(input.text, input.hash)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn remove_hash_from_index_a(input: HashResultInput) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.remove_result_for_hash(input.text, input.hash)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_a_with_hashes(
+    input: SearchIndexInput,
+) -> ExternResult<Vec<(String, Vec<AnyLinkableHash>)>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_results_with_hashes(input.query, input.limit)
+}
+
+#[hdk_extern]
+pub fn add_document_to_index_a(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?
+    .with_tokenizer(make_document_tokenizer());
+
+    index.add_document(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn remove_document_from_index_a(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?
+    .with_tokenizer(make_document_tokenizer());
+
+    index.remove_document(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_a_all_terms(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?
+    .with_tokenizer(make_document_tokenizer());
+
+    index.get_results_all_terms(input.query, input.limit)
+}
+
+fn make_document_tokenizer() -> Tokenizer {
+    Tokenizer::new(
+        WordBoundary::Unicode,
+        vec!["the".into(), "and".into(), "for".into()],
+    )
+}
+
 #[hdk_extern]
 pub fn get_random_results_index_a(limit: usize) -> ExternResult<Vec<String>> {
     let index = PrefixIndex::new(
@@ -59,6 +311,49 @@ pub fn get_random_results_index_a(limit: usize) -> ExternResult<Vec<String>> {
     index.get_random_results(limit)
 }
 
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct RandomResultsSeededInput {
+    pub limit: usize,
+    pub seed: [u8; 32],
+}
+#[hdk_extern]
+pub fn get_random_results_seeded_index_a(
+    input: RandomResultsSeededInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_random_results_seeded(input.limit, input.seed)
+}
+
+#[hdk_extern]
+pub fn get_uniform_random_results_index_a(limit: usize) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_uniform_random_results(limit)
+}
+
+#[hdk_extern]
+pub fn get_random_results_for_prefix_index_a(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.get_random_results_for_prefix(input.query, input.limit)
+}
+
 #[hdk_extern]
 pub fn add_to_index_b(text: String) -> ExternResult<()> {
     let index = PrefixIndex::new(
@@ -111,6 +406,259 @@ pub fn search_index_c(input: SearchIndexInput) -> ExternResult<Vec<String>> {
     index.get_results(input.query, input.limit)
 }
 
+#[hdk_extern]
+pub fn add_to_index_d(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_D_NAME.into(),
+        LinkTypes::PrefixIndexD,
+        PREFIX_INDEX_D_WIDTH,
+        PREFIX_INDEX_D_DEPTH,
+    )?
+    .with_shard_unit(PREFIX_INDEX_D_SHARD_UNIT);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_d_with_options(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_D_NAME.into(),
+        LinkTypes::PrefixIndexD,
+        PREFIX_INDEX_D_WIDTH,
+        PREFIX_INDEX_D_DEPTH,
+    )?
+    .with_shard_unit(PREFIX_INDEX_D_SHARD_UNIT);
+
+    index.get_results_with_options(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn add_to_index_e(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_E_NAME.into(),
+        LinkTypes::PrefixIndexE,
+        PREFIX_INDEX_E_WIDTH,
+        PREFIX_INDEX_E_DEPTH,
+    )?
+    .with_normalization(PREFIX_INDEX_E_NORMALIZATION.to_vec());
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn remove_from_index_e(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_E_NAME.into(),
+        LinkTypes::PrefixIndexE,
+        PREFIX_INDEX_E_WIDTH,
+        PREFIX_INDEX_E_DEPTH,
+    )?
+    .with_normalization(PREFIX_INDEX_E_NORMALIZATION.to_vec());
+
+    index.remove_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_e_with_options(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_E_NAME.into(),
+        LinkTypes::PrefixIndexE,
+        PREFIX_INDEX_E_WIDTH,
+        PREFIX_INDEX_E_DEPTH,
+    )?
+    .with_normalization(PREFIX_INDEX_E_NORMALIZATION.to_vec());
+
+    index.get_results_with_options(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn add_to_index_f(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_F_NAME.into(),
+        LinkTypes::PrefixIndexF,
+        PREFIX_INDEX_F_WIDTH,
+        PREFIX_INDEX_F_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_F_CASE_MODE)
+    .with_locale(PREFIX_INDEX_F_LOCALE.into());
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_f_with_options(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_F_NAME.into(),
+        LinkTypes::PrefixIndexF,
+        PREFIX_INDEX_F_WIDTH,
+        PREFIX_INDEX_F_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_F_CASE_MODE)
+    .with_locale(PREFIX_INDEX_F_LOCALE.into());
+
+    index.get_results_with_options(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn add_to_index_g(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_G_NAME.into(),
+        LinkTypes::PrefixIndexG,
+        PREFIX_INDEX_G_WIDTH,
+        PREFIX_INDEX_G_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_G_CASE_MODE);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_g_with_options(
+    input: SearchIndexWithOptionsInput,
+) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_G_NAME.into(),
+        LinkTypes::PrefixIndexG,
+        PREFIX_INDEX_G_WIDTH,
+        PREFIX_INDEX_G_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_G_CASE_MODE);
+
+    index.get_results_with_options(input.query, input.options)
+}
+
+#[hdk_extern]
+pub fn add_to_index_h(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn remove_from_index_h(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.remove_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_h(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.get_results(input.query, input.limit)
+}
+
+#[hdk_extern]
+pub fn add_hash_to_index_h(input: HashResultInput) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.add_result_for_hash(input.text, input.hash)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn remove_hash_from_index_h(input: HashResultInput) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.remove_result_for_hash(input.text, input.hash)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_h_with_hashes(
+    input: SearchIndexInput,
+) -> ExternResult<Vec<(String, Vec<AnyLinkableHash>)>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    index.get_results_with_hashes(input.query, input.limit)
+}
+
+#[hdk_extern]
+pub fn add_to_index_i(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_I_NAME.into(),
+        LinkTypes::PrefixIndexI,
+        PREFIX_INDEX_I_WIDTH,
+        PREFIX_INDEX_I_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_I_INDEX_MODE);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_i_ngram(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_I_NAME.into(),
+        LinkTypes::PrefixIndexI,
+        PREFIX_INDEX_I_WIDTH,
+        PREFIX_INDEX_I_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_I_INDEX_MODE);
+
+    index.get_results_ngram(input.query, input.limit)
+}
+
 #[hdk_extern]
 pub fn add_hashtag_to_index_a(hashtag: String) -> ExternResult<()> {
     let index = PrefixIndex::new(
@@ -178,3 +726,90 @@ pub fn remove_cashtag_from_index_a(cashtag: String) -> ExternResult<()> {
 fn make_cashtag_index_text(text: String) -> String {
     text.split('$').nth(1).unwrap_or(&text).to_string()
 }
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct LabelledResultInput {
+    pub text: String,
+    pub label: String,
+}
+#[hdk_extern]
+pub fn add_labelled_result_to_index_a(input: LabelledResultInput) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_A_NAME.into(),
+        LinkTypes::PrefixIndexA,
+        PREFIX_INDEX_A_WIDTH,
+        PREFIX_INDEX_A_DEPTH,
+    )?;
+
+    index.add_result_with_label(input.text, input.label)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn add_to_index_j(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_J_NAME.into(),
+        LinkTypes::PrefixIndexJ,
+        PREFIX_INDEX_J_WIDTH,
+        PREFIX_INDEX_J_DEPTH,
+    )?
+    .with_normalization(vec![NormalizationStep::Custom(
+        PREFIX_INDEX_J_NORMALIZER.into(),
+    )])
+    .with_custom_normalizer(PREFIX_INDEX_J_NORMALIZER.into(), Leetspeak);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn search_index_j(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_J_NAME.into(),
+        LinkTypes::PrefixIndexJ,
+        PREFIX_INDEX_J_WIDTH,
+        PREFIX_INDEX_J_DEPTH,
+    )?
+    .with_normalization(vec![NormalizationStep::Custom(
+        PREFIX_INDEX_J_NORMALIZER.into(),
+    )])
+    .with_custom_normalizer(PREFIX_INDEX_J_NORMALIZER.into(), Leetspeak);
+
+    index.get_results(input.query, input.limit)
+}
+
+/// Same as add_to_index_j, but forgetting to add the custom normalizer
+#[hdk_extern]
+pub fn add_to_index_j_without_normalizer(text: String) -> ExternResult<()> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_J_NAME.into(),
+        LinkTypes::PrefixIndexJ,
+        PREFIX_INDEX_J_WIDTH,
+        PREFIX_INDEX_J_DEPTH,
+    )?
+    .with_normalization(vec![NormalizationStep::Custom(
+        PREFIX_INDEX_J_NORMALIZER.into(),
+    )]);
+
+    index.add_result(text)?;
+
+    Ok(())
+}
+
+/// Same as search_index_j, but forgetting to add the custom normalizer
+#[hdk_extern]
+pub fn search_index_j_without_normalizer(input: SearchIndexInput) -> ExternResult<Vec<String>> {
+    let index = PrefixIndex::new(
+        PREFIX_INDEX_J_NAME.into(),
+        LinkTypes::PrefixIndexJ,
+        PREFIX_INDEX_J_WIDTH,
+        PREFIX_INDEX_J_DEPTH,
+    )?
+    .with_normalization(vec![NormalizationStep::Custom(
+        PREFIX_INDEX_J_NORMALIZER.into(),
+    )]);
+
+    index.get_results(input.query, input.limit)
+}
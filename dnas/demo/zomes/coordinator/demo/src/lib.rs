@@ -1,4 +1,5 @@
 use hdk::prelude::*;
+pub mod benchmark;
 pub mod demo_prefix_index;
 pub use demo_prefix_index::*;
 
@@ -1,4 +1,10 @@
-use demo::SearchIndexInput;
+use demo::{
+    EstimateCountInput, HashResultInput, LabelledResultInput, RandomResultsSeededInput,
+    SearchIndexFuzzyInput, SearchIndexInput, SearchIndexPageInput, SearchIndexWithOptionsInput,
+};
+use hc_prefix_index::types::{
+    BrowseNode, MatchMode, Ordering, Page, SearchOptions, SearchResult, SearchStats,
+};
 use hdk::prelude::*;
 use holochain::{conductor::config::ConductorConfig, prelude::DnaFile, sweettest::*};
 
@@ -977,3 +983,2616 @@ async fn get_random_results_returns_random_results() {
     unique_results.insert(&results5[0]);
     assert!(unique_results.len() > 1)
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn add_result_for_hashes() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let alice_hash: AnyLinkableHash = alice.agent_pubkey().clone().into();
+    let bob_hash: AnyLinkableHash = bob.agent_pubkey().clone().into();
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: bob_hash.clone(),
+            },
+        )
+        .await;
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("supercomputing"),
+                hash: bob_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<(String, Vec<AnyLinkableHash>)> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_hashes",
+            SearchIndexInput {
+                query: "superdupercool".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, String::from("superdupercool"));
+    assert_eq!(results[0].1.len(), 2);
+    assert!(results[0].1.contains(&alice_hash));
+    assert!(results[0].1.contains(&bob_hash));
+    assert_eq!(
+        results[1],
+        (String::from("supercomputing"), vec![bob_hash.clone()])
+    );
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_hash_from_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<(String, Vec<AnyLinkableHash>)> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_hashes",
+            SearchIndexInput {
+                query: "superdupercool".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            (String::from("superdupercool"), vec![bob_hash.clone()]),
+            (String::from("supercomputing"), vec![bob_hash.clone()])
+        ]
+    );
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_hash_from_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: bob_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "superdupercool".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("supercomputing")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_results_in_pages() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "supersaturates",
+        "superb",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let page1: Page = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_page",
+            SearchIndexPageInput {
+                query: "superdupe".into(),
+                limit: 2,
+                cursor: None,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        page1.results,
+        vec![
+            String::from("superdupercool"),
+            String::from("superdupercrazy")
+        ]
+    );
+    assert!(page1.next_cursor.is_some());
+
+    let page2: Page = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_page",
+            SearchIndexPageInput {
+                query: "superdupe".into(),
+                limit: 2,
+                cursor: page1.next_cursor,
+            },
+        )
+        .await;
+
+    assert_eq!(page2.results.len(), 2);
+    assert!(!page2.results.iter().any(|r| page1.results.contains(r)));
+    assert!(page2.next_cursor.is_some());
+
+    let page3: Page = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_page",
+            SearchIndexPageInput {
+                query: "superdupe".into(),
+                limit: 2,
+                cursor: page2.next_cursor,
+            },
+        )
+        .await;
+
+    assert_eq!(page3.results.len(), 1);
+    assert!(page3.next_cursor.is_none());
+
+    let all_results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "superdupe".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        all_results,
+        [page1.results, page2.results, page3.results].concat()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_with_min_depth() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "supersaturates",
+        "holochain",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "superduperbad".into(),
+                options: SearchOptions {
+                    min_depth: 3,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            String::from("superdupercool"),
+            String::from("superdupercrazy")
+        ]
+    );
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "supersaturday".into(),
+                options: SearchOptions {
+                    min_depth: 2,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("supersaturates")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "supersaturday".into(),
+                options: SearchOptions::new(10),
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!(!results.contains(&String::from("holochain")));
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "cow".into(),
+                options: SearchOptions {
+                    min_depth: 0,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 5);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_with_strict_match_mode() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "supersaturates",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let strict = |query: &str| SearchIndexWithOptionsInput {
+        query: query.into(),
+        options: SearchOptions {
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(10)
+        },
+    };
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            strict("superdupercool"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("superdupercool")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            strict("SuperDuperCr"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("superdupercrazy")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            strict("supe"),
+        )
+        .await;
+
+    assert_eq!(results.len(), 4);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            strict("supersaturday"),
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn round_trip_results_containing_path_syntax_characters() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let texts = [
+        "v1.2 release",
+        "a#b",
+        "12:30",
+        "https://holochain.org/docs?page=1#top",
+        "report.final.v2.pdf",
+        "..hidden",
+    ];
+
+    for text in texts {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    for text in texts {
+        let results: Vec<String> = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "search_index_a_with_options",
+                SearchIndexWithOptionsInput {
+                    query: text.into(),
+                    options: SearchOptions {
+                        match_mode: MatchMode::Strict,
+                        ..SearchOptions::new(10)
+                    },
+                },
+            )
+            .await;
+
+        assert_eq!(results, vec![String::from(text)]);
+    }
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "v1.2".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("v1.2 release")]);
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_from_index_a",
+            String::from("report.final.v2.pdf"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "report".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shard_by_grapheme_clusters() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let texts = [
+        // Thumbs up with a skin tone modifier
+        "\u{1F44D}\u{1F3FD}ok",
+        "\u{1F44D}\u{1F3FD}no",
+        // 'e' followed by a combining acute accent
+        "cafe\u{301}s",
+        "cafes",
+        // Thai: consonants carrying vowel and tone marks
+        "\u{0E15}\u{0E49}\u{0E21}\u{0E22}\u{0E33}",
+    ];
+
+    for text in texts {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_d", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let strict = |query: &str| SearchIndexWithOptionsInput {
+        query: query.into(),
+        options: SearchOptions {
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(10)
+        },
+    };
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_d_with_options",
+            strict("\u{1F44D}\u{1F3FD}o"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("\u{1F44D}\u{1F3FD}ok")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_d_with_options",
+            strict("cafe\u{301}"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("cafe\u{301}s")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_d_with_options",
+            strict("\u{0E15}\u{0E49}\u{0E21}"),
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![String::from("\u{0E15}\u{0E49}\u{0E21}\u{0E22}\u{0E33}")]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn normalize_text_when_indexing_and_searching() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "Café  Society!",
+        "\u{FF28}\u{FF2F}\u{FF2C}\u{FF2F} chain",
+        "déjà-vu",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_e", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let strict = |query: &str| SearchIndexWithOptionsInput {
+        query: query.into(),
+        options: SearchOptions {
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(10)
+        },
+    };
+
+    for query in ["cafe soc", "CAFÉ", "ca\u{301}fe\u{301}   society"] {
+        let results: Vec<String> = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "search_index_e_with_options",
+                strict(query),
+            )
+            .await;
+
+        assert_eq!(results, vec![String::from("Café  Society!")]);
+    }
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_e_with_options",
+            strict("holo"),
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![String::from("\u{FF28}\u{FF2F}\u{FF2C}\u{FF2F} chain")]
+    );
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_e_with_options",
+            strict("dejavu"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("déjà-vu")]);
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_from_index_e",
+            String::from("Café  Society!"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_e_with_options",
+            strict("cafe"),
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fold_case_following_locale() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["İSTANBUL", "ILIK", "STRASSE", "Straße", "ΟΔΥΣΣΕΥΣ"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_f", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let strict = |query: &str| SearchIndexWithOptionsInput {
+        query: query.into(),
+        options: SearchOptions {
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(10)
+        },
+    };
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_f_with_options",
+            strict("istanbul"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("İSTANBUL")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_f_with_options",
+            strict("ılık"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("ILIK")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_f_with_options",
+            strict("straße"),
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&String::from("STRASSE")));
+    assert!(results.contains(&String::from("Straße")));
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_f_with_options",
+            strict("οδυσσευς"),
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("ΟΔΥΣΣΕΥΣ")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn case_sensitive_index_keeps_letter_case_apart() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["AAPL", "aapl", "aGVsbG8=", "AGVSBG8="] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_g", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_g_with_options",
+            SearchIndexWithOptionsInput {
+                query: "AAP".into(),
+                options: SearchOptions::new(10),
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("AAPL")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_g_with_options",
+            SearchIndexWithOptionsInput {
+                query: "aap".into(),
+                options: SearchOptions::new(10),
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("aapl")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_g_with_options",
+            SearchIndexWithOptionsInput {
+                query: "aGVsbG8=".into(),
+                options: SearchOptions {
+                    match_mode: MatchMode::Strict,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("aGVsbG8=")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn add_documents_searchable_by_any_word() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["Holochain Developer Meetup", "The Rust and Holochain Meetup"] {
+        let _: () = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "add_document_to_index_a",
+                String::from(text),
+            )
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "dev".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("Holochain Developer Meetup")]);
+
+    // Matches on several words, but each document is returned once
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "hol".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&String::from("Holochain Developer Meetup")));
+    assert!(results.contains(&String::from("The Rust and Holochain Meetup")));
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "meetup".into(),
+                options: SearchOptions {
+                    match_mode: MatchMode::Strict,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+
+    // Stop words are not indexed
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "the".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_document_from_index_a",
+            String::from("Holochain Developer Meetup"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "meet".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("The Rust and Holochain Meetup")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_documents_matching_all_terms() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "Rust Meetup Berlin",
+        "Rust Meetup Lisbon",
+        "Holochain Meetup Berlin",
+    ] {
+        let _: () = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "add_document_to_index_a",
+                String::from(text),
+            )
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_all_terms",
+            SearchIndexInput {
+                query: "rust meetup berlin".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("Rust Meetup Berlin")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_all_terms",
+            SearchIndexInput {
+                query: "meet ber".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&String::from("Rust Meetup Berlin")));
+    assert!(results.contains(&String::from("Holochain Meetup Berlin")));
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_all_terms",
+            SearchIndexInput {
+                query: "go meetup".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_with_typos() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputer",
+        "holochain",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // A typo in the first shard, which get_results can't recover from
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: "sipercomp".into(),
+                max_edits: 1,
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("supercomputer")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: "holochian".into(),
+                max_edits: 2,
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("holochain")]);
+
+    // Closest first
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: "superdupercoo".into(),
+                max_edits: 2,
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            String::from("superdupercool"),
+            String::from("superdupercrazy")
+        ]
+    );
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: "xyz".into(),
+                max_edits: 1,
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn find_results_by_any_part_with_suffix_index_mode() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["supercomputing", "computer", "holochain"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_h", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_h",
+            SearchIndexInput {
+                query: "comp".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&String::from("supercomputing")));
+    assert!(results.contains(&String::from("computer")));
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_h",
+            SearchIndexInput {
+                query: "chain".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("holochain")]);
+
+    // Removing a result also removes it from under its suffixes
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_from_index_h",
+            String::from("supercomputing"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_h",
+            SearchIndexInput {
+                query: "comp".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("computer")]);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_h",
+            SearchIndexInput {
+                query: "sup".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn find_results_by_fragments_with_ngram_index_mode() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["holochain", "blockchain", "hologram"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_i", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // "holochain" shares "och", "cha" and "hai", "blockchain" only "cha" and "hai"
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_i_ngram",
+            SearchIndexInput {
+                query: "ochai".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![String::from("holochain"), String::from("blockchain")]
+    );
+
+    // Ties are ordered alphabetically
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_i_ngram",
+            SearchIndexInput {
+                query: "holo".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![String::from("holochain"), String::from("hologram")]
+    );
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_i_ngram",
+            SearchIndexInput {
+                query: "ochai".into(),
+                limit: 1,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("holochain")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_ranks_results_by_score() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "supercomputing",
+        "superdupercrazy",
+        "superdupercool",
+        "superduper",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<SearchResult> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_ranked",
+            SearchIndexWithOptionsInput {
+                query: "superduper".into(),
+                options: SearchOptions::new(10),
+            },
+        )
+        .await;
+
+    // Exact match first, then the longest shared prefix, then the shortest result
+    let labels: Vec<String> = results.iter().map(|r| r.label.clone()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            String::from("superduper"),
+            String::from("superdupercool"),
+            String::from("superdupercrazy"),
+            String::from("supercomputing"),
+        ]
+    );
+
+    let matched_prefix_lens: Vec<usize> = results.iter().map(|r| r.matched_prefix_len).collect();
+    assert_eq!(matched_prefix_lens, vec![10, 10, 10, 5]);
+
+    assert!(results[0].score > results[1].score);
+    assert!(results[1].score > results[2].score);
+    assert!(results[2].score > results[3].score);
+
+    assert_eq!(
+        results[1].path,
+        vec![
+            String::from("prefix_index_a"),
+            String::from("sup"),
+            String::from("erd"),
+            String::from("upe"),
+            String::from("superdupercool"),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn order_results_alphabetically() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["Zebra", "banana", "Oslo", "Apricot", "Øresund", "Äpfel", "apple"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let options = SearchOptions {
+        min_depth: 0,
+        ordering: Ordering::Alphabetical,
+        ..SearchOptions::new(10)
+    };
+
+    // Accents and letter case don't push results to the end, "Ø" sorts as an "O" with a stroke
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "zeb".into(),
+                options: options.clone(),
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            String::from("Äpfel"),
+            String::from("apple"),
+            String::from("Apricot"),
+            String::from("banana"),
+            String::from("Øresund"),
+            String::from("Oslo"),
+            String::from("Zebra"),
+        ]
+    );
+
+    // The whole result set is sorted before the limit applies
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "zeb".into(),
+                options: SearchOptions { limit: 2, ..options },
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("Äpfel"), String::from("apple")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn order_results_by_recency() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["superdupercool", "supercomputing", "superb"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "sup".into(),
+                options: SearchOptions {
+                    ordering: Ordering::Newest,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            String::from("superb"),
+            String::from("supercomputing"),
+            String::from("superdupercool"),
+        ]
+    );
+
+    let results: Vec<SearchResult> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_ranked",
+            SearchIndexWithOptionsInput {
+                query: "sup".into(),
+                options: SearchOptions {
+                    ordering: Ordering::Oldest,
+                    ..SearchOptions::new(10)
+                },
+            },
+        )
+        .await;
+
+    let labels: Vec<String> = results.iter().map(|r| r.label.clone()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            String::from("superdupercool"),
+            String::from("supercomputing"),
+            String::from("superb"),
+        ]
+    );
+    assert!(results[0].timestamp < results[1].timestamp);
+    assert!(results[1].timestamp < results[2].timestamp);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn order_results_by_popularity() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["superdupercool", "supercomputing", "superb"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "add_to_index_a",
+            String::from("supercomputing"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let options = SearchOptions {
+        ordering: Ordering::Popularity,
+        ..SearchOptions::new(10)
+    };
+
+    let results: Vec<SearchResult> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_ranked",
+            SearchIndexWithOptionsInput {
+                query: "sup".into(),
+                options: options.clone(),
+            },
+        )
+        .await;
+
+    let labels: Vec<String> = results.iter().map(|r| r.label.clone()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            String::from("supercomputing"),
+            String::from("superb"),
+            String::from("superdupercool"),
+        ]
+    );
+    let popularities: Vec<usize> = results.iter().map(|r| r.popularity).collect();
+    assert_eq!(popularities, vec![2, 1, 1]);
+
+    // Bob no longer adds it, but Alice still does
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "remove_from_index_a",
+            String::from("supercomputing"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_options",
+            SearchIndexWithOptionsInput {
+                query: "sup".into(),
+                options,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            String::from("superb"),
+            String::from("supercomputing"),
+            String::from("superdupercool"),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_results_without_fetching_them() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "superb",
+        "holochain",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let count: usize = conductors[0]
+        .call(&alice.zome("demo"), "count_index_a", String::from("sup"))
+        .await;
+    assert_eq!(count, 4);
+
+    let count: usize = conductors[0]
+        .call(&alice.zome("demo"), "count_index_a", String::from("superd"))
+        .await;
+    assert_eq!(count, 2);
+
+    let count: usize = conductors[0]
+        .call(&alice.zome("demo"), "count_index_a", String::from("walrus"))
+        .await;
+    assert_eq!(count, 0);
+
+    let mut counts: Vec<(String, usize)> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "count_index_a_by_child",
+            String::from("sup"),
+        )
+        .await;
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            (String::from("erb"), 1),
+            (String::from("erc"), 1),
+            (String::from("erd"), 2),
+        ]
+    );
+
+    // Every branch below the levels counted exactly holds the same number of results, so the estimate is exact
+    for levels in [1, 3] {
+        let estimate: usize = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "estimate_count_index_a",
+                EstimateCountInput {
+                    query: "sup".into(),
+                    levels,
+                },
+            )
+            .await;
+        assert_eq!(estimate, 4);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn browse_index_one_shard_at_a_time() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "holochain",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let nodes: Vec<BrowseNode> = conductors[0]
+        .call(&alice.zome("demo"), "browse_index_a", String::from(""))
+        .await;
+
+    assert_eq!(
+        nodes,
+        vec![
+            BrowseNode {
+                component: String::from("hol"),
+                full_prefix: String::from("hol"),
+                is_leaf: false,
+                child_count: 1,
+            },
+            BrowseNode {
+                component: String::from("sup"),
+                full_prefix: String::from("sup"),
+                is_leaf: false,
+                child_count: 2,
+            },
+        ]
+    );
+
+    let nodes: Vec<BrowseNode> = conductors[0]
+        .call(&alice.zome("demo"), "browse_index_a", String::from("sup"))
+        .await;
+
+    let full_prefixes: Vec<String> = nodes.iter().map(|n| n.full_prefix.clone()).collect();
+    assert_eq!(
+        full_prefixes,
+        vec![String::from("superc"), String::from("superd")]
+    );
+
+    let nodes: Vec<BrowseNode> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "browse_index_a",
+            String::from("superdupe"),
+        )
+        .await;
+
+    assert_eq!(
+        nodes,
+        vec![
+            BrowseNode {
+                component: String::from("superdupercool"),
+                full_prefix: String::from("superdupercool"),
+                is_leaf: true,
+                child_count: 0,
+            },
+            BrowseNode {
+                component: String::from("superdupercrazy"),
+                full_prefix: String::from("superdupercrazy"),
+                is_leaf: true,
+                child_count: 0,
+            },
+        ]
+    );
+
+    // A partial shard only lists the shards continuing it
+    let nodes: Vec<BrowseNode> = conductors[0]
+        .call(&alice.zome("demo"), "browse_index_a", String::from("h"))
+        .await;
+
+    let components: Vec<String> = nodes.iter().map(|n| n.component.clone()).collect();
+    assert_eq!(components, vec![String::from("hol")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_random_results_for_prefix_stays_within_prefix() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in ["holochain", "hologram", "holiday", "superdupercool"] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "get_random_results_for_prefix_index_a",
+            SearchIndexInput {
+                query: "hol".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    let results: HashSet<String> = results.into_iter().collect();
+    assert_eq!(
+        results,
+        HashSet::from([
+            String::from("holochain"),
+            String::from("hologram"),
+            String::from("holiday"),
+        ])
+    );
+
+    // A partial shard narrows the results down further
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "get_random_results_for_prefix_index_a",
+            SearchIndexInput {
+                query: "holo".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    let results: HashSet<String> = results.into_iter().collect();
+    assert_eq!(
+        results,
+        HashSet::from([String::from("holochain"), String::from("hologram")])
+    );
+
+    let mut unique_results = HashSet::new();
+    for _ in 0..10 {
+        let results: Vec<String> = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "get_random_results_for_prefix_index_a",
+                SearchIndexInput {
+                    query: "hol".into(),
+                    limit: 1,
+                },
+            )
+            .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].starts_with("hol"));
+        unique_results.insert(results[0].clone());
+    }
+
+    // Assert we did not get the exact same result 10 times
+    assert!(unique_results.len() > 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_uniform_random_results_picks_every_result_equally() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    // "holochain" is alone in its branch, the other 9 results share theirs
+    let mut texts = vec![String::from("holochain")];
+    for i in 0..9 {
+        texts.push(format!("superdupercool{}", i));
+    }
+    for text in texts.iter() {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", text.clone())
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let samples = 200;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for _ in 0..samples {
+        let results: Vec<String> = conductors[0]
+            .call(&alice.zome("demo"), "get_uniform_random_results_index_a", 1)
+            .await;
+
+        assert_eq!(results.len(), 1);
+        *counts.entry(results[0].clone()).or_insert(0) += 1;
+    }
+
+    // Every result is picked with probability 1/10, so each is expected 20 times.
+    // Missing any of them has a probability under 1e-8.
+    for text in texts.iter() {
+        assert!(counts.contains_key(text), "{} was never picked", text);
+    }
+
+    // Shuffling children at each level would pick "holochain" about half the time, 100 times.
+    // Picking it 45 times or more is about 6 standard deviations above the expected 20.
+    let holochain_count = counts.get("holochain").copied().unwrap_or(0);
+    assert!(
+        holochain_count < 45,
+        "holochain was picked {} times out of {}",
+        holochain_count,
+        samples
+    );
+
+    let results: Vec<String> = conductors[0]
+        .call(&alice.zome("demo"), "get_uniform_random_results_index_a", 3)
+        .await;
+
+    let unique_results: HashSet<String> = results.iter().cloned().collect();
+    assert_eq!(unique_results.len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_random_results_seeded_is_reproducible() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "holochain",
+        "hologram",
+        "holiday",
+        "walrus",
+        "wallaby",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Every agent gets the same results for the same seed
+    let alice_results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "get_random_results_seeded_index_a",
+            RandomResultsSeededInput {
+                limit: 3,
+                seed: [7; 32],
+            },
+        )
+        .await;
+    let bob_results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "get_random_results_seeded_index_a",
+            RandomResultsSeededInput {
+                limit: 3,
+                seed: [7; 32],
+            },
+        )
+        .await;
+
+    assert_eq!(alice_results.len(), 3);
+    assert_eq!(alice_results, bob_results);
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "get_random_results_seeded_index_a",
+            RandomResultsSeededInput {
+                limit: 3,
+                seed: [7; 32],
+            },
+        )
+        .await;
+
+    assert_eq!(results, alice_results);
+
+    // Assert other seeds don't all give the exact same results
+    let mut unique_results = HashSet::new();
+    for seed in 0..5 {
+        let results: Vec<String> = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "get_random_results_seeded_index_a",
+                RandomResultsSeededInput {
+                    limit: 3,
+                    seed: [seed; 32],
+                },
+            )
+            .await;
+        unique_results.insert(results);
+    }
+    assert!(unique_results.len() > 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_fetches_each_level_with_a_single_get_links_call() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "superdupercool",
+        "superdupercrazy",
+        "supercomputing",
+        "supersaturates",
+    ] {
+        let _: () = conductors[0]
+            .call(&alice.zome("demo"), "add_to_index_a", String::from(text))
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let (results, stats): (Vec<String>, SearchStats) = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_with_stats",
+            SearchIndexWithOptionsInput {
+                query: "sup".into(),
+                options: SearchOptions::new(10),
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!([
+        String::from("superdupercool"),
+        String::from("superdupercrazy"),
+        String::from("supercomputing"),
+        String::from("supersaturates"),
+    ]
+    .iter()
+    .all(|item| results.contains(item)));
+
+    // The query path, the children of "sup" to look it up and climb to its siblings, then one call per shard level below "sup"
+    // Fetching each path on its own would take 12 calls, one for each of the 8 paths and 4 leaves
+    assert_eq!(stats.get_links_calls, 4);
+    assert_eq!(stats.paths_fetched, 8);
+
+    // Stops fetching levels once limit is reached
+    let (results, limited_stats): (Vec<String>, SearchStats) = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_with_stats",
+            SearchIndexWithOptionsInput {
+                query: "superdupercool".into(),
+                options: SearchOptions::new(1),
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("superdupercool")]);
+    assert_eq!(limited_stats.get_links_calls, 1);
+}
+
+/// Benchmark of the traversal on an index of 10k results, against the traversal it replaced,
+/// run with `cargo test benchmark -- --ignored --nocapture`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "benchmark, takes several minutes to build the index"]
+async fn benchmark_search_index_with_10k_results() {
+    let dna = load_dna().await;
+    let mut conductor = SweetConductor::from_config(ConductorConfig::default()).await;
+    let alice = conductor
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_cells()
+        .remove(0);
+
+    // 6 letter words spread evenly over the index, 7919 being coprime with 26^6 they are all distinct
+    let texts: Vec<String> = (0..10_000u64)
+        .map(|i| {
+            let mut n = i * 7919 % 26u64.pow(6);
+            (0..6)
+                .map(|_| {
+                    let letter = (b'a' + (n % 26) as u8) as char;
+                    n /= 26;
+                    letter
+                })
+                .collect()
+        })
+        .collect();
+    for chunk in texts.chunks(500) {
+        let _: () = conductor
+            .call(&alice.zome("demo"), "add_many_to_index_a", chunk.to_vec())
+            .await;
+    }
+
+    let _ = await_consistency(600, [&alice]).await;
+
+    for (query, limit) in [("a", 10), ("abc", 10), ("abcdef", 100), ("a", 10_000)] {
+        let start = std::time::Instant::now();
+        let (results, stats): (Vec<String>, SearchStats) = conductor
+            .call(
+                &alice.zome("demo"),
+                "search_index_a_with_stats",
+                SearchIndexWithOptionsInput {
+                    query: query.into(),
+                    options: SearchOptions {
+                        min_depth: 0,
+                        ..SearchOptions::new(limit)
+                    },
+                },
+            )
+            .await;
+        println!(
+            "get_results('{}', {}): {} results in {:?}, {:?}",
+            query,
+            limit,
+            results.len(),
+            start.elapsed(),
+            stats
+        );
+
+        assert_eq!(results.len(), limit);
+
+        // The baseline clones its state for every child, so it is only compared on smaller searches
+        if limit > 100 {
+            continue;
+        }
+
+        let start = std::time::Instant::now();
+        let (baseline_results, baseline_get_links_calls): (Vec<String>, usize) = conductor
+            .call(
+                &alice.zome("demo"),
+                "search_index_a_baseline",
+                SearchIndexInput {
+                    query: query.into(),
+                    limit,
+                },
+            )
+            .await;
+        println!(
+            "baseline get_results('{}', {}): {} results in {:?}, {} get_links calls",
+            query,
+            limit,
+            baseline_results.len(),
+            start.elapsed(),
+            baseline_get_links_calls
+        );
+
+        assert_eq!(baseline_results.len(), limit);
+        assert!(stats.get_links_calls < baseline_get_links_calls);
+    }
+
+    let start = std::time::Instant::now();
+    let results: Vec<String> = conductor
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: texts[0].clone(),
+                max_edits: 2,
+                limit: 10,
+            },
+        )
+        .await;
+    println!(
+        "get_results_fuzzy: {} results in {:?}",
+        results.len(),
+        start.elapsed()
+    );
+
+    let start = std::time::Instant::now();
+    let count: usize = conductor
+        .call(&alice.zome("demo"), "count_index_a", String::from("a"))
+        .await;
+    println!("count_results('a'): {} in {:?}", count, start.elapsed());
+
+    assert_eq!(
+        count,
+        texts.iter().filter(|text| text.starts_with('a')).count()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remove_results_added_by_different_agents() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_to_index_a",
+            String::from("superdupercool"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Bob's result shares the shards Alice already linked
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "add_to_index_a",
+            String::from("superdupercrazy"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_from_index_a",
+            String::from("superdupercool"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "remove_from_index_a",
+            String::from("superdupercrazy"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Bob can't delete the shards Alice linked, but they are not returned as results
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "sup".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, Vec::<String>::new());
+
+    let count: usize = conductors[1]
+        .call(&bob.zome("demo"), "count_index_a", String::from("sup"))
+        .await;
+
+    assert_eq!(count, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remove_hashes_attached_by_different_agents() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let alice_hash: AnyLinkableHash = alice.agent_pubkey().clone().into();
+    let bob_hash: AnyLinkableHash = bob.agent_pubkey().clone().into();
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Bob attaches the same hash to the same result
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Alice only detaches her own link, Bob's is left in place
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_hash_from_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<(String, Vec<AnyLinkableHash>)> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a_with_hashes",
+            SearchIndexInput {
+                query: "superdupercool".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![(String::from("superdupercool"), vec![alice_hash.clone()])]
+    );
+
+    let _: () = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "remove_hash_from_index_a",
+            HashResultInput {
+                text: String::from("superdupercool"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "superdupercool".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(results, Vec::<String>::new());
+
+    // Removing a result detaches its hashes, so they don't come back when it is added again
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_a",
+            HashResultInput {
+                text: String::from("supercomputing"),
+                hash: bob_hash.clone(),
+            },
+        )
+        .await;
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_from_index_a",
+            String::from("supercomputing"),
+        )
+        .await;
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_to_index_a",
+            String::from("supercomputing"),
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<(String, Vec<AnyLinkableHash>)> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_with_hashes",
+            SearchIndexInput {
+                query: "supercomputing".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![(String::from("supercomputing"), vec![])]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_hashes_of_results_found_by_suffix() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let alice_hash: AnyLinkableHash = alice.agent_pubkey().clone().into();
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "add_hash_to_index_h",
+            HashResultInput {
+                text: String::from("supercomputing"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<(String, Vec<AnyLinkableHash>)> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_h_with_hashes",
+            SearchIndexInput {
+                query: "comp".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(
+        results,
+        vec![(String::from("supercomputing"), vec![alice_hash.clone()])]
+    );
+
+    let _: () = conductors[0]
+        .call(
+            &alice.zome("demo"),
+            "remove_hash_from_index_h",
+            HashResultInput {
+                text: String::from("supercomputing"),
+                hash: alice_hash.clone(),
+            },
+        )
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_h",
+            SearchIndexInput {
+                query: "comp".into(),
+                limit: 5,
+            },
+        )
+        .await;
+
+    assert_eq!(results, Vec::<String>::new());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn normalize_text_with_a_custom_normalizer() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let _: () = conductors[0]
+        .call(&alice.zome("demo"), "add_to_index_j", String::from("h0l0ch41n"))
+        .await;
+    let _: () = conductors[0]
+        .call(&alice.zome("demo"), "add_to_index_j", String::from("walrus"))
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Digits are read as the letters they stand for, both when indexing and when searching
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_j",
+            SearchIndexInput {
+                query: "holochain".into(),
+                limit: 1,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("h0l0ch41n")]);
+
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_j",
+            SearchIndexInput {
+                query: "w4lru5".into(),
+                limit: 1,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("walrus")]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_all_terms_from_the_smallest_subtree() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for text in [
+        "Rust Meetup Berlin",
+        "Rust Meetup Lisbon",
+        "Rust Meetup Paris",
+        "Rust Meetup Madrid",
+        "Holochain Meetup Lisbon",
+    ] {
+        let _: () = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "add_document_to_index_a",
+                String::from(text),
+            )
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // "lisbon" has fewer documents below it than the longer "meetup"
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_all_terms",
+            SearchIndexInput {
+                query: "rust meetup lisbon".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results, vec![String::from("Rust Meetup Lisbon")]);
+
+    // Nothing is indexed below "tokyo"
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_all_terms",
+            SearchIndexInput {
+                query: "rust meetup tokyo".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_results_with_labels_unrelated_to_their_text() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    for (text, label) in [("holochain", "HC Project"), ("holoch", "HC")] {
+        let _: () = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "add_labelled_result_to_index_a",
+                LabelledResultInput {
+                    text: text.into(),
+                    label: label.into(),
+                },
+            )
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a",
+            SearchIndexInput {
+                query: "hol".into(),
+                limit: 10,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&String::from("HC Project")));
+    assert!(results.contains(&String::from("HC")));
+
+    let count: usize = conductors[1]
+        .call(&bob.zome("demo"), "count_index_a", String::from("hol"))
+        .await;
+
+    assert_eq!(count, 2);
+
+    // "HC" sits where a shard could, but a shard is never upper case
+    let nodes: Vec<BrowseNode> = conductors[1]
+        .call(&bob.zome("demo"), "browse_index_a", String::from("holoch"))
+        .await;
+
+    assert_eq!(
+        nodes,
+        vec![
+            BrowseNode {
+                component: String::from("ain"),
+                full_prefix: String::from("holochain"),
+                is_leaf: false,
+                child_count: 1,
+            },
+            BrowseNode {
+                component: String::from("HC"),
+                full_prefix: String::from("HC"),
+                is_leaf: true,
+                child_count: 0,
+            },
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_with_typos_finds_more_results_than_get_links_calls() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    // More results than MAX_FUZZY_GET_LINKS_CALLS, all below the same shards
+    for i in 0..110 {
+        let _: () = conductors[0]
+            .call(
+                &alice.zome("demo"),
+                "add_to_index_a",
+                format!("supercomputer{i:03}"),
+            )
+            .await;
+    }
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    let results: Vec<String> = conductors[1]
+        .call(
+            &bob.zome("demo"),
+            "search_index_a_fuzzy",
+            SearchIndexFuzzyInput {
+                query: "sipercomp".into(),
+                max_edits: 1,
+                limit: 110,
+            },
+        )
+        .await;
+
+    assert_eq!(results.len(), 110);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn custom_normalizer_must_be_added() {
+    let dna = load_dna().await;
+    let mut conductors: SweetConductorBatch =
+        SweetConductorBatch::from_config(2, ConductorConfig::default()).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("demo", &[dna])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let _: () = conductors[0]
+        .call(&alice.zome("demo"), "add_to_index_j", String::from("h0l0ch41n"))
+        .await;
+
+    let _ = await_consistency(60, [&alice, &bob]).await;
+
+    // Text would otherwise be sharded differently than by the agents who added the normalizer
+    let result = conductors[1]
+        .call_fallible::<_, ()>(
+            &bob.zome("demo"),
+            "add_to_index_j_without_normalizer",
+            String::from("h0l0gram"),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    let result = conductors[1]
+        .call_fallible::<_, Vec<String>>(
+            &bob.zome("demo"),
+            "search_index_j_without_normalizer",
+            SearchIndexInput {
+                query: "holo".into(),
+                limit: 1,
+            },
+        )
+        .await;
+
+    assert!(result.is_err());
+}
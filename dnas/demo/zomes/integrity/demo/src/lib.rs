@@ -1,3 +1,5 @@
+use hc_prefix_index::normalize::{CaseMode, NormalizationStep, Normalizer};
+use hc_prefix_index::types::{IndexMode, ShardUnit};
 use hc_prefix_index::PrefixIndex;
 use hdi::prelude::*;
 
@@ -13,12 +15,80 @@ pub const PREFIX_INDEX_C_NAME: &str = "prefix_index_c";
 pub const PREFIX_INDEX_C_WIDTH: usize = 4;
 pub const PREFIX_INDEX_C_DEPTH: usize = 2;
 
+pub const PREFIX_INDEX_D_NAME: &str = "prefix_index_d";
+pub const PREFIX_INDEX_D_WIDTH: usize = 2;
+pub const PREFIX_INDEX_D_DEPTH: usize = 2;
+pub const PREFIX_INDEX_D_SHARD_UNIT: ShardUnit = ShardUnit::Grapheme;
+
+pub const PREFIX_INDEX_E_NAME: &str = "prefix_index_e";
+pub const PREFIX_INDEX_E_WIDTH: usize = 3;
+pub const PREFIX_INDEX_E_DEPTH: usize = 3;
+pub const PREFIX_INDEX_E_NORMALIZATION: [NormalizationStep; 5] = [
+    NormalizationStep::FullWidthToHalfWidth,
+    NormalizationStep::Nfkc,
+    NormalizationStep::FoldDiacritics,
+    NormalizationStep::StripPunctuation,
+    NormalizationStep::CollapseWhitespace,
+];
+
+pub const PREFIX_INDEX_F_NAME: &str = "prefix_index_f";
+pub const PREFIX_INDEX_F_WIDTH: usize = 3;
+pub const PREFIX_INDEX_F_DEPTH: usize = 3;
+pub const PREFIX_INDEX_F_CASE_MODE: CaseMode = CaseMode::Fold;
+pub const PREFIX_INDEX_F_LOCALE: &str = "tr";
+
+pub const PREFIX_INDEX_G_NAME: &str = "prefix_index_g";
+pub const PREFIX_INDEX_G_WIDTH: usize = 3;
+pub const PREFIX_INDEX_G_DEPTH: usize = 3;
+pub const PREFIX_INDEX_G_CASE_MODE: CaseMode = CaseMode::Sensitive;
+
+pub const PREFIX_INDEX_H_NAME: &str = "prefix_index_h";
+pub const PREFIX_INDEX_H_WIDTH: usize = 3;
+pub const PREFIX_INDEX_H_DEPTH: usize = 3;
+pub const PREFIX_INDEX_H_INDEX_MODE: IndexMode = IndexMode::Suffix { min_suffix_len: 4 };
+
+pub const PREFIX_INDEX_I_NAME: &str = "prefix_index_i";
+pub const PREFIX_INDEX_I_WIDTH: usize = 3;
+pub const PREFIX_INDEX_I_DEPTH: usize = 3;
+pub const PREFIX_INDEX_I_INDEX_MODE: IndexMode = IndexMode::NGram { n: 3 };
+
+pub const PREFIX_INDEX_J_NAME: &str = "prefix_index_j";
+pub const PREFIX_INDEX_J_WIDTH: usize = 3;
+pub const PREFIX_INDEX_J_DEPTH: usize = 3;
+pub const PREFIX_INDEX_J_NORMALIZER: &str = "leetspeak";
+
+/// Reads digits used as letters as those letters, so "h0l0ch41n" is found by typing "holo"
+pub struct Leetspeak;
+
+impl Normalizer for Leetspeak {
+    fn normalize(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '0' => 'o',
+                '1' => 'i',
+                '3' => 'e',
+                '4' => 'a',
+                '5' => 's',
+                '7' => 't',
+                _ => c,
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
 pub enum LinkTypes {
     PrefixIndexA,
     PrefixIndexB,
     PrefixIndexC,
+    PrefixIndexD,
+    PrefixIndexE,
+    PrefixIndexF,
+    PrefixIndexG,
+    PrefixIndexH,
+    PrefixIndexI,
+    PrefixIndexJ,
 }
 
 // Validation you perform during the genesis process. Nobody else on the network performs it, only you.
@@ -80,6 +150,66 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         PREFIX_INDEX_C_DEPTH,
     )?;
 
+    let prefix_index_d = PrefixIndex::new(
+        PREFIX_INDEX_D_NAME.into(),
+        LinkTypes::PrefixIndexD,
+        PREFIX_INDEX_D_WIDTH,
+        PREFIX_INDEX_D_DEPTH,
+    )?
+    .with_shard_unit(PREFIX_INDEX_D_SHARD_UNIT);
+
+    let prefix_index_e = PrefixIndex::new(
+        PREFIX_INDEX_E_NAME.into(),
+        LinkTypes::PrefixIndexE,
+        PREFIX_INDEX_E_WIDTH,
+        PREFIX_INDEX_E_DEPTH,
+    )?
+    .with_normalization(PREFIX_INDEX_E_NORMALIZATION.to_vec());
+
+    let prefix_index_f = PrefixIndex::new(
+        PREFIX_INDEX_F_NAME.into(),
+        LinkTypes::PrefixIndexF,
+        PREFIX_INDEX_F_WIDTH,
+        PREFIX_INDEX_F_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_F_CASE_MODE)
+    .with_locale(PREFIX_INDEX_F_LOCALE.into());
+
+    let prefix_index_g = PrefixIndex::new(
+        PREFIX_INDEX_G_NAME.into(),
+        LinkTypes::PrefixIndexG,
+        PREFIX_INDEX_G_WIDTH,
+        PREFIX_INDEX_G_DEPTH,
+    )?
+    .with_case_mode(PREFIX_INDEX_G_CASE_MODE);
+
+    let prefix_index_h = PrefixIndex::new(
+        PREFIX_INDEX_H_NAME.into(),
+        LinkTypes::PrefixIndexH,
+        PREFIX_INDEX_H_WIDTH,
+        PREFIX_INDEX_H_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_H_INDEX_MODE);
+
+    let prefix_index_i = PrefixIndex::new(
+        PREFIX_INDEX_I_NAME.into(),
+        LinkTypes::PrefixIndexI,
+        PREFIX_INDEX_I_WIDTH,
+        PREFIX_INDEX_I_DEPTH,
+    )?
+    .with_index_mode(PREFIX_INDEX_I_INDEX_MODE);
+
+    let prefix_index_j = PrefixIndex::new(
+        PREFIX_INDEX_J_NAME.into(),
+        LinkTypes::PrefixIndexJ,
+        PREFIX_INDEX_J_WIDTH,
+        PREFIX_INDEX_J_DEPTH,
+    )?
+    .with_normalization(vec![NormalizationStep::Custom(
+        PREFIX_INDEX_J_NORMALIZER.into(),
+    )])
+    .with_custom_normalizer(PREFIX_INDEX_J_NORMALIZER.into(), Leetspeak);
+
     match op.flattened::<(), LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry {
@@ -115,6 +245,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::PrefixIndexA => prefix_index_a.validate_create_link(action),
             LinkTypes::PrefixIndexB => prefix_index_b.validate_create_link(action),
             LinkTypes::PrefixIndexC => prefix_index_c.validate_create_link(action),
+            LinkTypes::PrefixIndexD => prefix_index_d.validate_create_link(action),
+            LinkTypes::PrefixIndexE => prefix_index_e.validate_create_link(action),
+            LinkTypes::PrefixIndexF => prefix_index_f.validate_create_link(action),
+            LinkTypes::PrefixIndexG => prefix_index_g.validate_create_link(action),
+            LinkTypes::PrefixIndexH => prefix_index_h.validate_create_link(action),
+            LinkTypes::PrefixIndexI => prefix_index_i.validate_create_link(action),
+            LinkTypes::PrefixIndexJ => prefix_index_j.validate_create_link(action),
         },
         FlatOp::RegisterDeleteLink {
             link_type,
@@ -127,6 +264,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::PrefixIndexA => prefix_index_a.validate_delete_link(action, original_action),
             LinkTypes::PrefixIndexB => prefix_index_b.validate_delete_link(action, original_action),
             LinkTypes::PrefixIndexC => prefix_index_c.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexD => prefix_index_d.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexE => prefix_index_e.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexF => prefix_index_f.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexG => prefix_index_g.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexH => prefix_index_h.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexI => prefix_index_i.validate_delete_link(action, original_action),
+            LinkTypes::PrefixIndexJ => prefix_index_j.validate_delete_link(action, original_action),
         },
         FlatOp::StoreRecord(store_record) => match store_record {
             // Complementary validation to the `StoreEntry` Op, in which the record itself is validated
@@ -163,6 +307,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 LinkTypes::PrefixIndexA => prefix_index_a.validate_create_link(action),
                 LinkTypes::PrefixIndexB => prefix_index_b.validate_create_link(action),
                 LinkTypes::PrefixIndexC => prefix_index_c.validate_create_link(action),
+                LinkTypes::PrefixIndexD => prefix_index_d.validate_create_link(action),
+                LinkTypes::PrefixIndexE => prefix_index_e.validate_create_link(action),
+                LinkTypes::PrefixIndexF => prefix_index_f.validate_create_link(action),
+                LinkTypes::PrefixIndexG => prefix_index_g.validate_create_link(action),
+                LinkTypes::PrefixIndexH => prefix_index_h.validate_create_link(action),
+                LinkTypes::PrefixIndexI => prefix_index_i.validate_create_link(action),
+                LinkTypes::PrefixIndexJ => prefix_index_j.validate_create_link(action),
             },
             // Complementary validation to the `RegisterDeleteLink` Op, in which the record itself is validated
             // If you want to optimize performance, you can remove the validation for an entry type here and keep it in `RegisterDeleteLink`
@@ -199,6 +350,27 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     LinkTypes::PrefixIndexC => {
                         prefix_index_c.validate_delete_link(action, create_link)
                     }
+                    LinkTypes::PrefixIndexD => {
+                        prefix_index_d.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexE => {
+                        prefix_index_e.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexF => {
+                        prefix_index_f.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexG => {
+                        prefix_index_g.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexH => {
+                        prefix_index_h.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexI => {
+                        prefix_index_i.validate_delete_link(action, create_link)
+                    }
+                    LinkTypes::PrefixIndexJ => {
+                        prefix_index_j.validate_delete_link(action, create_link)
+                    }
                 }
             }
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
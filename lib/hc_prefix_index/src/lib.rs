@@ -3,7 +3,10 @@
 //! A crate for prefix indexing of labelled hashes
 //!
 //! Useful for type-ahead "search" or autocomplete features.
+pub mod normalize;
 pub mod prefix_index;
 pub use crate::prefix_index::PrefixIndex;
+pub mod tokenize;
+pub mod types;
 mod utils;
 mod validate;
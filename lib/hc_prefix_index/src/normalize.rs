@@ -0,0 +1,244 @@
+use caseless::default_case_fold_str;
+use feruca::Collator;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Transforms text before it is sharded,
+/// so that the different ways a user may type the same text end up in the same shards
+pub trait Normalizer {
+    fn normalize(&self, text: &str) -> String;
+
+    /// Whether a fragment of normalized text, i.e. a shard component, is left untouched by this normalizer
+    fn is_normalized(&self, fragment: &str) -> bool {
+        self.normalize(fragment) == fragment
+    }
+}
+
+/// Normalization steps, applied in the order they are configured on a PrefixIndex
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NormalizationStep {
+    /// Unicode compatibility composition (NFKC), i.e. "ﬁ" -> "fi", "①" -> "1"
+    Nfkc,
+    /// Remove combining marks, i.e. "Café" -> "Cafe"
+    ///
+    /// Meant for latin, greek and cyrillic scripts: it also removes the vowel signs of scripts like thai or devanagari.
+    FoldDiacritics,
+    /// Replace runs of whitespace with a single space, and trim whitespace at both ends
+    CollapseWhitespace,
+    /// Remove punctuation, i.e. "rock'n'roll!" -> "rocknroll"
+    StripPunctuation,
+    /// Replace full-width ASCII variants with their half-width equivalents, i.e. "ＡＢＣ" -> "ABC"
+    FullWidthToHalfWidth,
+    /// A Normalizer of your own, added to the PrefixIndex with with_custom_normalizer under this name
+    ///
+    /// Only the name is serialized, so the integrity and coordinator zomes must both add the normalizer.
+    /// Indexing, searching and validation fail when the normalizer wasn't added.
+    Custom(String),
+}
+
+impl Normalizer for NormalizationStep {
+    fn normalize(&self, text: &str) -> String {
+        match self {
+            NormalizationStep::Nfkc => text.nfkc().collect(),
+            NormalizationStep::FoldDiacritics => text
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect(),
+            NormalizationStep::CollapseWhitespace => {
+                text.split_whitespace().collect::<Vec<&str>>().join(" ")
+            }
+            NormalizationStep::StripPunctuation => {
+                text.chars().filter(|c| !is_punctuation(*c)).collect()
+            }
+            NormalizationStep::FullWidthToHalfWidth => text.chars().map(to_half_width).collect(),
+            // Resolved by NormalizationPipeline
+            NormalizationStep::Custom(_) => text.to_string(),
+        }
+    }
+
+    fn is_normalized(&self, fragment: &str) -> bool {
+        match self {
+            // A fragment may start or end in the middle of a run of whitespace
+            NormalizationStep::CollapseWhitespace => {
+                !fragment.contains("  ") && fragment.chars().all(|c| c == ' ' || !c.is_whitespace())
+            }
+            _ => self.normalize(fragment) == fragment,
+        }
+    }
+}
+
+/// Normalizers of your own, by the name NormalizationStep::Custom refers to them with
+#[derive(Clone, Default)]
+pub struct CustomNormalizers(BTreeMap<String, Rc<dyn Normalizer>>);
+
+impl CustomNormalizers {
+    pub fn insert(&mut self, name: String, normalizer: Rc<dyn Normalizer>) {
+        self.0.insert(name, normalizer);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Normalizer> {
+        self.0.get(name).map(|normalizer| normalizer.as_ref())
+    }
+}
+
+impl fmt::Debug for CustomNormalizers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Normalizers can't be compared, so only their names are
+impl PartialEq for CustomNormalizers {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.keys().eq(other.0.keys())
+    }
+}
+
+/// Normalization steps applied in order, along with the custom normalizers they may refer to
+///
+/// Steps naming a custom normalizer that wasn't added are skipped, so check the pipeline before normalizing with it.
+pub struct NormalizationPipeline<'a> {
+    pub steps: &'a [NormalizationStep],
+    pub custom: &'a CustomNormalizers,
+}
+
+impl NormalizationPipeline<'_> {
+    /// Fails if a custom step refers to a normalizer that wasn't added,
+    /// since text would then be normalized differently than by the zomes that did add it
+    pub fn check(&self) -> ExternResult<()> {
+        for step in self.steps.iter() {
+            if let NormalizationStep::Custom(name) = step {
+                if self.custom.get(name).is_none() {
+                    return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                        "custom normalizer '{name}' must be added with with_custom_normalizer"
+                    ))));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn normalizer<'a>(&'a self, step: &'a NormalizationStep) -> Option<&'a dyn Normalizer> {
+        match step {
+            NormalizationStep::Custom(name) => self.custom.get(name),
+            _ => Some(step),
+        }
+    }
+}
+
+impl Normalizer for NormalizationPipeline<'_> {
+    fn normalize(&self, text: &str) -> String {
+        self.steps
+            .iter()
+            .filter_map(|step| self.normalizer(step))
+            .fold(text.to_string(), |normalized, normalizer| {
+                normalizer.normalize(&normalized)
+            })
+    }
+
+    fn is_normalized(&self, fragment: &str) -> bool {
+        self.steps
+            .iter()
+            .filter_map(|step| self.normalizer(step))
+            .all(|normalizer| normalizer.is_normalized(fragment))
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '\u{00A1}'
+                | '\u{00A7}'
+                | '\u{00AB}'
+                | '\u{00B6}'
+                | '\u{00B7}'
+                | '\u{00BB}'
+                | '\u{00BF}'
+                // General punctuation: dashes, quotation marks, ellipsis, etc.
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                // CJK punctuation
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                // Full-width punctuation
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF20}'
+                | '\u{FF3B}'..='\u{FF40}'
+                | '\u{FF5B}'..='\u{FF65}'
+        )
+}
+
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// How letter case is handled when sharding text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CaseMode {
+    /// str::to_lowercase
+    #[default]
+    Lowercase,
+    /// Unicode full case folding, i.e. "STRASSE", "Straße" and "strasse" all become "strasse"
+    Fold,
+    /// Case is preserved, so "ABC" and "abc" are distinct results in distinct shards
+    Sensitive,
+}
+
+/// Convert the case of `text` following `case_mode`, tailored to `locale` (a BCP 47 language tag) if any
+///
+/// Text is returned as is with CaseMode::Sensitive.
+///
+/// Tailored locales:
+/// - "tr", "az": dotted and dotless i are distinct letters, "I" -> "ı" and "İ" -> "i"
+/// - "de": "ß" -> "ss", which CaseMode::Fold already does
+/// - "el": final sigma "ς" -> "σ", which CaseMode::Fold already does
+///
+/// Other locales get the default conversion.
+pub fn convert_case(text: &str, case_mode: CaseMode, locale: Option<&str>) -> String {
+    // Locales only tailor case conversion, so they don't apply to case sensitive text
+    let language = match case_mode {
+        CaseMode::Sensitive => None,
+        CaseMode::Lowercase | CaseMode::Fold => locale
+            .and_then(|l| l.split(['-', '_']).next())
+            .map(|l| l.to_lowercase()),
+    };
+
+    let text = match language.as_deref() {
+        Some("tr") | Some("az") => text.replace('I', "ı").replace('İ', "i"),
+        _ => text.to_string(),
+    };
+
+    let converted = match case_mode {
+        CaseMode::Sensitive => text,
+        CaseMode::Lowercase => text.to_lowercase(),
+        CaseMode::Fold => default_case_fold_str(&text),
+    };
+
+    match language.as_deref() {
+        Some("de") => converted.replace(['ß', 'ẞ'], "ss"),
+        Some("el") => converted.replace('ς', "σ"),
+        _ => converted,
+    }
+}
+
+/// Sort `items` by their text following the Unicode Collation Algorithm, with the CLDR root collation order
+///
+/// Letters are compared ignoring accents and case first, then accents, then case,
+/// and punctuation and spaces only break ties, i.e. "Äpfel" < "apple" < "Banana" and "Øresund" < "Oslo".
+/// Texts that collate the same are ordered by their bytes.
+pub fn sort_by_collation<T>(items: &mut [T], text: impl Fn(&T) -> String) {
+    let mut collator = Collator::default();
+    items.sort_by(|a, b| collator.collate(&text(a), &text(b)));
+}
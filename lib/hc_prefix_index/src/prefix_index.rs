@@ -1,7 +1,17 @@
+use crate::normalize::*;
+use crate::tokenize::*;
+use crate::types::Ordering;
+use crate::types::*;
 use crate::utils::*;
 use crate::validate::*;
 use hdk::prelude::*;
 use rand::prelude::*;
+use rand_chacha::ChaCha20Rng;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::rc::Rc;
+
+/// Added to the score of results that are exactly the query
+const EXACT_MATCH_BOOST: f64 = 1.0;
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
 pub struct PrefixIndex {
@@ -9,6 +19,24 @@ pub struct PrefixIndex {
     pub link_type: ScopedLinkType,
     pub width: usize,
     pub depth: usize,
+    #[serde(default)]
+    pub shard_unit: ShardUnit,
+    #[serde(default)]
+    pub index_mode: IndexMode,
+    /// Applied to text before it is sharded, both when indexing and when searching
+    #[serde(default)]
+    pub normalization: Vec<NormalizationStep>,
+    /// Normalizers of your own that NormalizationStep::Custom steps refer to, by name
+    #[serde(skip)]
+    pub custom_normalizers: CustomNormalizers,
+    #[serde(default)]
+    pub case_mode: CaseMode,
+    /// BCP 47 language tag tailoring the case conversion, i.e. "tr" for turkish dotted and dotless i
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Splits documents added with add_document into words
+    #[serde(default)]
+    pub tokenizer: Tokenizer,
 }
 
 impl PrefixIndex {
@@ -27,9 +55,59 @@ impl PrefixIndex {
             link_type: link_type.try_into()?,
             width,
             depth,
+            shard_unit: ShardUnit::default(),
+            normalization: vec![],
+            custom_normalizers: CustomNormalizers::default(),
+            case_mode: CaseMode::default(),
+            locale: None,
+            tokenizer: Tokenizer::default(),
+            index_mode: IndexMode::default(),
         })
     }
 
+    /// Count the width of the index in `shard_unit`s, i.e. graphemes instead of chars
+    pub fn with_shard_unit(mut self, shard_unit: ShardUnit) -> Self {
+        self.shard_unit = shard_unit;
+        self
+    }
+
+    /// Normalize text with `steps`, in order, before it is sharded
+    pub fn with_normalization(mut self, steps: Vec<NormalizationStep>) -> Self {
+        self.normalization = steps;
+        self
+    }
+
+    /// Add a Normalizer of your own, applied where `NormalizationStep::Custom(name)` is in the normalization steps
+    pub fn with_custom_normalizer(
+        mut self,
+        name: String,
+        normalizer: impl Normalizer + 'static,
+    ) -> Self {
+        self.custom_normalizers.insert(name, Rc::new(normalizer));
+        self
+    }
+
+    pub fn with_case_mode(mut self, case_mode: CaseMode) -> Self {
+        self.case_mode = case_mode;
+        self
+    }
+
+    /// Tailor the case conversion to `locale`, a BCP 47 language tag such as "tr" or "de"
+    pub fn with_locale(mut self, locale: String) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    pub fn with_index_mode(mut self, index_mode: IndexMode) -> Self {
+        self.index_mode = index_mode;
+        self
+    }
+
     pub fn add_result(&self, text: String) -> ExternResult<TypedPath> {
         self.inner_add_result(text, None)
     }
@@ -42,20 +120,120 @@ impl PrefixIndex {
         self.inner_add_result(text, Some(full_text))
     }
 
+    /// Add a result to the index and attach `hash` to it
+    ///
+    /// Many hashes can be attached to the same result, i.e. several entries sharing one label.
+    pub fn add_result_for_hash(
+        &self,
+        text: String,
+        hash: AnyLinkableHash,
+    ) -> ExternResult<TypedPath> {
+        let mut typed_paths = self.inner_add_result_paths(text, None)?;
+
+        // The result may be found at any of its paths, i.e. under a suffix of it
+        for typed_path in typed_paths.iter() {
+            create_link(
+                typed_path.path_entry_hash()?,
+                hash.clone(),
+                self.link_type,
+                LinkTag::new(RESULT_TARGET_LINK_TAG),
+            )?;
+        }
+
+        Ok(typed_paths.remove(0))
+    }
+
+    /// Add `text` as a result under each of its words, so it can be found by typing any of them
+    pub fn add_document(&self, text: String) -> ExternResult<Vec<TypedPath>> {
+        let words = self.document_words(&text);
+        if words.is_empty() {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "text must contain at least one word to index".into()
+            )));
+        }
+
+        words
+            .into_iter()
+            .map(|word| self.inner_add_result(word, Some(text.clone())))
+            .collect()
+    }
+
     fn inner_add_result(&self, text: String, full_text: Option<String>) -> ExternResult<TypedPath> {
-        let typed_path = self
-            .make_result_path(text.clone(), full_text)?
-            .typed(self.link_type)?;
+        Ok(self.inner_add_result_paths(text, full_text)?.remove(0))
+    }
 
-        typed_path.ensure()?;
+    /// Add a result under all its index keys, returning their paths, the one of its own text first
+    fn inner_add_result_paths(
+        &self,
+        text: String,
+        full_text: Option<String>,
+    ) -> ExternResult<Vec<TypedPath>> {
+        if self.index_key(&text).is_empty() {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "text must not be empty once normalized".into()
+            )));
+        }
+
+        let typed_paths = self.key_paths(&text, full_text)?;
+        for typed_path in typed_paths.iter() {
+            typed_path.ensure()?;
+            self.link_as_author(typed_path)?;
+        }
 
         debug!(
             "Added result '{:?}' to path {:?}",
             text,
-            path_to_string(typed_path.clone())
+            path_to_string(typed_paths[0].clone())
         );
 
-        Ok(typed_path)
+        Ok(typed_paths)
+    }
+
+    /// Paths of a result under each of its index keys, the one of its own text first
+    fn key_paths(&self, text: &str, full_text: Option<String>) -> ExternResult<Vec<TypedPath>> {
+        self.normalizer().check()?;
+
+        let label = full_text.unwrap_or(text.to_string());
+
+        self.index_keys(text)
+            .into_iter()
+            .map(|key| {
+                self.make_key_path(&key, label.clone())
+                    .typed(self.link_type)
+            })
+            .collect()
+    }
+
+    /// Link `path` from its parent as this agent, even if other agents already did,
+    /// so the number of distinct authors of those links tells how popular the result is
+    fn link_as_author(&self, path: &TypedPath) -> ExternResult<()> {
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+
+        let agent = agent_info()?.agent_initial_pubkey;
+        let path_entry_hash = path.path_entry_hash()?;
+        let links = get_links(
+            GetLinksInputBuilder::try_new(
+                parent.path_entry_hash()?,
+                LinkTypeFilter::single_type(self.link_type.zome_index, self.link_type.zome_type),
+            )?
+            .build(),
+        )?;
+
+        let is_linked = links.into_iter().any(|link| {
+            link.author == agent && link.target.into_entry_hash() == Some(path_entry_hash.clone())
+        });
+        if !is_linked {
+            create_link(
+                parent.path_entry_hash()?,
+                path_entry_hash,
+                self.link_type,
+                path_link_tag(path)?,
+            )?;
+        }
+
+        Ok(())
     }
 
     pub fn remove_result(&self, text: String) -> ExternResult<()> {
@@ -66,16 +244,66 @@ impl PrefixIndex {
         self.inner_remove_result(text, Some(full_text))
     }
 
+    /// Remove a result added with add_document from under each of its words
+    pub fn remove_document(&self, text: String) -> ExternResult<()> {
+        for word in self.document_words(&text).into_iter() {
+            self.inner_remove_result(word, Some(text.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Detach `hash` from a result
+    ///
+    /// Only the hashes this agent attached are detached, and the result itself is only removed
+    /// once this agent has no other hashes attached to it.
+    /// Like with remove_result, the result stays in the index for as long as other agents add it.
+    pub fn remove_result_for_hash(&self, text: String, hash: AnyLinkableHash) -> ExternResult<()> {
+        let mut remaining = vec![];
+        for path in self.key_paths(&text, None)?.into_iter() {
+            let (removed, others): (Vec<Link>, Vec<Link>) = self
+                .own_target_links(path)?
+                .into_iter()
+                .partition(|link| link.target == hash);
+
+            for link in removed.into_iter() {
+                delete_link(link.create_link_hash)?;
+            }
+            remaining.extend(others);
+        }
+
+        if remaining.is_empty() {
+            self.inner_remove_result(text, None)?;
+        }
+
+        Ok(())
+    }
+
     fn inner_remove_result(&self, text: String, full_text: Option<String>) -> ExternResult<()> {
-        let path = self
-            .make_result_path(text, full_text)?
-            .typed(self.link_type)?;
+        for path in self.key_paths(&text, full_text)?.into_iter() {
+            // So that adding the result again doesn't bring back the hashes attached to it
+            for link in self.own_target_links(path.clone())?.into_iter() {
+                delete_link(link.create_link_hash)?;
+            }
 
-        self.inner_remove_result_from_path(path)?;
+            self.inner_remove_result_from_path(path)?;
+        }
 
         Ok(())
     }
 
+    /// Links from the leaf `path` of a result to the hashes this agent attached to it
+    ///
+    /// Only the original author can delete a link, so other agents' links are left in place.
+    fn own_target_links(&self, path: TypedPath) -> ExternResult<Vec<Link>> {
+        let agent = agent_info()?.agent_initial_pubkey;
+
+        Ok(get_result_target_links(path)?
+            .into_iter()
+            .filter(|link| link.author == agent)
+            .collect())
+    }
+
     fn inner_remove_result_from_path(&self, path: TypedPath) -> ExternResult<()> {
         if path.exists()? {
             if let Some(parent) = path.parent() {
@@ -105,25 +333,29 @@ impl PrefixIndex {
                     .collect();
 
                 // Delete children link corresponding to current path
+                // Only the original author can delete a link, so other agents' links are left in place
+                let agent = agent_info()?.agent_initial_pubkey;
                 for child in result_children.clone().into_iter() {
                     let maybe_eh = child.target.into_entry_hash();
                     if let Some(eh) = maybe_eh {
-                        if eh == path.path_entry_hash()? {
+                        if eh == path.path_entry_hash()? && child.author == agent {
                             delete_link(child.create_link_hash)?;
                         }
                     }
                 }
 
-                // Get other children of parent of path
-                let mut other_children = vec![];
-                for i in children.into_iter() {
-                    if !result_children.contains(&i) {
-                        other_children.push(i);
-                    }
+                // Other agents added the path too, so it stays in the index
+                if result_children.iter().any(|child| child.author != agent) {
+                    return Ok(());
                 }
 
+                // Other children of parent of path
+                let has_other_children = children
+                    .iter()
+                    .any(|c| c.target.clone().into_entry_hash() != Some(path_entry_hash.clone()));
+
                 // If there are no other children of parent of path, delete parent of path
-                if other_children.is_empty() && !parent.is_root() {
+                if !has_other_children && !parent.is_root() {
                     self.inner_remove_result_from_path(parent)?;
                 }
             }
@@ -133,23 +365,515 @@ impl PrefixIndex {
     }
 
     pub fn get_results(&self, query: String, limit: usize) -> ExternResult<Vec<String>> {
+        self.get_results_with_options(query, SearchOptions::new(limit))
+    }
+
+    pub fn get_results_with_options(
+        &self,
+        query: String,
+        options: SearchOptions,
+    ) -> ExternResult<Vec<String>> {
+        if options.limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let results = self.find_results(query, &options)?;
+
+        Ok(result_labels(results))
+    }
+
+    /// Like get_results_with_options, but also returns the host calls the search made
+    pub fn get_results_with_stats(
+        &self,
+        query: String,
+        options: SearchOptions,
+    ) -> ExternResult<(Vec<String>, SearchStats)> {
+        let (calls_before, paths_before) = get_links_stats();
+        let results = self.get_results_with_options(query, options)?;
+        let (calls_after, paths_after) = get_links_stats();
+
+        Ok((
+            results,
+            SearchStats {
+                get_links_calls: calls_after - calls_before,
+                paths_fetched: paths_after - paths_before,
+            },
+        ))
+    }
+
+    /// Like get_results_with_options, but ranks the results by score, then alphabetically
+    pub fn search(&self, query: String, options: SearchOptions) -> ExternResult<Vec<SearchResult>> {
+        if options.limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let key = self.index_key(&query);
+        let key_units = self.shard_unit.split(&key);
+
+        let mut results: Vec<SearchResult> = self
+            .find_results(query, &options)?
+            .into_iter()
+            .filter_map(|node| {
+                let label = node_label(&node)?;
+                let (score, matched_prefix_len) = self.score_label(&label, &key_units);
+
+                Some(SearchResult {
+                    label,
+                    score,
+                    matched_prefix_len,
+                    path: node
+                        .path
+                        .as_ref()
+                        .iter()
+                        .filter_map(|c| String::try_from(c).ok())
+                        .collect(),
+                    timestamp: node.timestamp?,
+                    popularity: node.popularity,
+                })
+            })
+            .collect();
+
+        // Other orderings are already applied by find_results
+        if options.ordering == Ordering::Relevance {
+            results.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.label.cmp(&b.label)));
+        }
+
+        Ok(results)
+    }
+
+    /// Get results matching every word of `query` by prefix, i.e. "rust meetup berlin"
+    ///
+    /// Meant for results added with add_document, which are indexed under each of their words.
+    /// Only the subtree of one term is searched, and its results are checked against the other terms as they are found.
+    /// That term is the one with the fewest children below its deepest full shard, fetched with a single get_links call,
+    /// as a heuristic for the smallest subtree, the longest term breaking ties.
+    /// Terms shorter than the index width can't narrow down the subtree, so when every
+    /// term is that short the whole index is searched.
+    /// No results are returned without searching when a term has nothing below it.
+    pub fn get_results_all_terms(&self, query: String, limit: usize) -> ExternResult<Vec<String>> {
         if limit == 0 {
             return Err(wasm_error!(WasmErrorInner::Guest(
                 "limit must be > 0".into()
             )));
         }
 
-        let path = self
-            .make_result_path(query.clone(), None)?
-            .typed(self.link_type)?;
+        self.normalizer().check()?;
 
-        debug!(
-            "Searching for '{:?}', starting at path '{:?}'",
-            query,
-            path_to_string(path.clone())
-        );
+        let mut terms: Vec<String> = self
+            .tokenizer
+            .words(&query)
+            .into_iter()
+            .map(|word| self.index_key(word))
+            .filter(|key| !key.is_empty())
+            .collect();
+        terms.sort_by(|a, b| {
+            self.shard_unit
+                .count(b)
+                .cmp(&self.shard_unit.count(a))
+                .then(a.cmp(b))
+        });
+        terms.dedup();
+
+        if terms.len() > 1 {
+            let roots = terms
+                .iter()
+                .map(|term| self.strict_root(term).into_typed(self.link_type))
+                .collect::<Vec<TypedPath>>();
+            let child_counts: Vec<usize> = get_child_nodes_batch(&roots)?
+                .iter()
+                .map(Vec::len)
+                .collect();
+
+            // No document can match a term with nothing below it
+            if child_counts.contains(&0) {
+                return Ok(vec![]);
+            }
+
+            // Terms are sorted longest first, and min_by_key keeps the first of equal counts
+            if let Some(smallest) = (0..terms.len()).min_by_key(|i| child_counts[*i]) {
+                let term = terms.remove(smallest);
+                terms.insert(0, term);
+            }
+        }
+
+        let Some((first_term, other_terms)) = terms.split_first() else {
+            return Ok(vec![]);
+        };
+
+        let options = SearchOptions {
+            min_depth: 0,
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(limit)
+        };
+        let mut cursor = self.make_cursor(first_term.clone(), &options)?;
 
-        self.inner_get_results(path, limit, false)
+        let mut results: Vec<String> = vec![];
+        while results.len() < limit && self.has_more(&cursor) {
+            let labels = result_labels(self.walk(&mut cursor, limit - results.len(), None)?);
+            results.extend(labels.into_iter().filter(|label| {
+                other_terms
+                    .iter()
+                    .all(|term| self.is_label_match(label, term))
+            }));
+        }
+
+        Ok(results)
+    }
+
+    /// Get results starting with `query` give or take `max_edits` typos, closest first
+    ///
+    /// The index is searched best-first from its name, expanding the paths that need the fewest edits so far
+    /// with a single get_links call, and giving up on those that need more than `max_edits`.
+    /// Paths below the deepest shard level are results, so they are never expanded.
+    /// At most MAX_FUZZY_GET_LINKS_CALLS get_links calls are made, so results may be missing from large indexes.
+    pub fn get_results_fuzzy(
+        &self,
+        query: String,
+        max_edits: usize,
+        limit: usize,
+    ) -> ExternResult<Vec<String>> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        self.normalizer().check()?;
+
+        let key = self.index_key(&query);
+        let key_units = self.shard_unit.split(&key);
+        let row: Vec<usize> = (0..=key_units.len()).collect();
+        let mut search = FuzzySearch::new(limit);
+        search.push(FuzzyNode {
+            path: self.index_path(),
+            distance: row[key_units.len()],
+            label_distance: usize::MAX,
+            row,
+        });
+
+        let mut get_links_calls = 0;
+
+        while get_links_calls < MAX_FUZZY_GET_LINKS_CALLS {
+            let closest = search.pop_closest();
+            let Some(min_distance) = closest.first().map(FuzzyNode::min_distance) else {
+                break;
+            };
+
+            // Nothing left can be closer than the results found so far
+            if search.is_complete(min_distance) {
+                break;
+            }
+
+            // Paths below the deepest shard level are always results, so their children are never fetched
+            let to_expand: Vec<TypedPath> = closest
+                .iter()
+                .filter(|node| !self.is_below_shards(&node.path))
+                .map(|node| node.path.clone().into_typed(self.link_type))
+                .collect();
+            if !to_expand.is_empty() {
+                get_links_calls += 1;
+            }
+            let mut expanded = get_child_nodes_batch(&to_expand)?.into_iter();
+
+            for node in closest.into_iter() {
+                let children = match self.is_below_shards(&node.path) {
+                    true => vec![],
+                    false => expanded.next().unwrap_or_default(),
+                };
+
+                if children.is_empty() {
+                    if let Some(label) = node.path.leaf().and_then(|c| String::try_from(c).ok()) {
+                        if node.label_distance <= max_edits && self.is_result_path(&node.path) {
+                            search.add_result(node.label_distance, label);
+                        }
+                    }
+                    continue;
+                }
+
+                for child in children.into_iter() {
+                    let Some(component) = node_label(&child) else {
+                        continue;
+                    };
+
+                    // The child is either a shard continuing the text so far, or the label of a result
+                    let mut row = node.row.clone();
+                    let mut distance = node.distance;
+                    for unit in self.shard_unit.split(&component).into_iter() {
+                        row = next_edit_row(&key_units, &row, unit);
+                        distance = distance.min(row[key_units.len()]);
+                    }
+
+                    let child = FuzzyNode {
+                        path: child.path,
+                        label_distance: self.label_edit_distance(&component, &key_units),
+                        row,
+                        distance,
+                    };
+                    if child.min_distance() <= max_edits {
+                        search.push(child);
+                    }
+                }
+            }
+        }
+
+        Ok(search.results.into_iter().map(|(_, label)| label).collect())
+    }
+
+    /// Get results sharing the most n-grams with `query`, for indexes using IndexMode::NGram
+    ///
+    /// Each n-gram of the query is looked up with a single get_links call,
+    /// and results are ranked by how many of them they share, then alphabetically.
+    /// Queries too short to have any n-grams are searched by prefix instead.
+    pub fn get_results_ngram(&self, query: String, limit: usize) -> ExternResult<Vec<String>> {
+        let IndexMode::NGram { n } = self.index_mode else {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "index mode must be NGram to search by n-grams".into()
+            )));
+        };
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        self.normalizer().check()?;
+
+        let grams = self.ngrams(&self.index_key(&query), n);
+        if grams.is_empty() {
+            return self.get_results(query, limit);
+        }
+
+        let mut shared_grams: BTreeMap<String, usize> = BTreeMap::new();
+        for gram in grams.iter() {
+            let mut path_components = self.index_path().as_ref().clone();
+            path_components.extend(self.make_shard_components(gram));
+            let path = Path::from(path_components).into_typed(self.link_type);
+
+            // Results indexed under this n-gram are leaves right below it, next to the shards of longer keys
+            for child in get_children_paths(path)?.into_iter() {
+                let maybe_label = child.leaf().and_then(|c| String::try_from(c).ok());
+                if let Some(label) = maybe_label {
+                    if self.label_keys(&label).contains(gram) {
+                        *shared_grams.entry(label).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<(usize, String)> = shared_grams
+            .into_iter()
+            .map(|(label, count)| (count, label))
+            .collect();
+        results.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        Ok(results
+            .into_iter()
+            .take(limit)
+            .map(|(_, label)| label)
+            .collect())
+    }
+
+    /// Number of results starting with `query`
+    ///
+    /// Every path below the query is visited, so it takes as many get_links calls as getting all those results.
+    pub fn count_results(&self, query: String) -> ExternResult<usize> {
+        let options = SearchOptions {
+            min_depth: 0,
+            match_mode: MatchMode::Strict,
+            ..SearchOptions::new(usize::MAX)
+        };
+        let mut cursor = self.make_cursor(query, &options)?;
+
+        Ok(self.walk(&mut cursor, usize::MAX, None)?.len())
+    }
+
+    /// Number of results starting with `query` below each component of the next shard level, i.e. for facets
+    ///
+    /// Results directly below the query's shards are counted under their own label.
+    pub fn count_results_by_child(&self, query: String) -> ExternResult<Vec<(String, usize)>> {
+        let key = self.index_key(&query);
+
+        let mut counts = vec![];
+        for child in self.prefix_children(&key)?.into_iter() {
+            let Some(component) = node_label(&child) else {
+                continue;
+            };
+
+            // Never climb above the child
+            let mut cursor = Cursor::new(child.path.clone(), shard_level(&child.path));
+            cursor.pending = vec![child];
+            cursor.strict_key = Some(key.clone());
+
+            counts.push((component, self.walk(&mut cursor, usize::MAX, None)?.len()));
+        }
+
+        Ok(counts)
+    }
+
+    /// Estimate the number of results starting with `query`, only visiting the top `levels` shard levels below it
+    ///
+    /// Those levels are counted exactly. Below them, random branches are followed down to a result,
+    /// multiplying the number of children at each step (Knuth's estimator),
+    /// from up to ESTIMATE_COUNT_PROBES paths of the deepest level counted.
+    pub fn estimate_count(&self, query: String, levels: usize) -> ExternResult<usize> {
+        let key = self.index_key(&query);
+
+        let mut leaves = 0;
+        let mut frontier = self.prefix_children(&key)?;
+        for _ in 1..levels {
+            let paths: Vec<TypedPath> = frontier
+                .into_iter()
+                .map(|node| node.path.into_typed(self.link_type))
+                .collect();
+
+            let mut next_frontier = vec![];
+            for (path, children) in paths.iter().zip(get_child_nodes_batch(&paths)?) {
+                match children.is_empty() {
+                    true => leaves += usize::from(self.is_result_path(path)),
+                    false => next_frontier.extend(children),
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        if frontier.is_empty() {
+            return Ok(leaves);
+        }
+
+        let mut rng = rand::thread_rng();
+        let probes: Vec<&Node> = frontier
+            .choose_multiple(&mut rng, ESTIMATE_COUNT_PROBES)
+            .collect();
+
+        let mut probed_leaves = 0.0;
+        for node in probes.iter() {
+            probed_leaves += self.probe_leaf_count(node.path.clone())?;
+        }
+        let estimate = frontier.len() as f64 * probed_leaves / probes.len() as f64;
+
+        Ok(leaves + estimate.round() as usize)
+    }
+
+    /// List the components right below `prefix`, alphabetically, to drill down the index one shard at a time
+    ///
+    /// An empty prefix lists the first shard level, "sup" the shards below it, etc.
+    /// A prefix ending in a partial shard only lists the shards continuing it.
+    /// The children of all the nodes listed are fetched with one more get_links call to count them.
+    pub fn browse(&self, prefix: String) -> ExternResult<Vec<BrowseNode>> {
+        let key = self.index_key(&prefix);
+
+        let children = self.prefix_children(&key)?;
+        let paths: Vec<TypedPath> = children
+            .iter()
+            .map(|child| child.path.clone().into_typed(self.link_type))
+            .collect();
+        let grandchildren = get_child_nodes_batch(&paths)?;
+
+        let mut nodes = vec![];
+        for (child, grandchildren) in children.into_iter().zip(grandchildren) {
+            let Some(component) = node_label(&child) else {
+                continue;
+            };
+
+            let child_count = grandchildren.len();
+            let is_leaf = child_count == 0;
+            // Shards left behind by removed results have nothing to drill down into
+            if is_leaf && !self.is_result_path(&child.path) {
+                continue;
+            }
+            let full_prefix = match is_leaf {
+                true => component.clone(),
+                false => child.path.as_ref()[1..]
+                    .iter()
+                    .filter_map(|c| String::try_from(c).ok())
+                    .collect(),
+            };
+
+            nodes.push(BrowseNode {
+                component,
+                full_prefix,
+                is_leaf,
+                child_count,
+            });
+        }
+
+        sort_by_collation(&mut nodes, |node| node.component.clone());
+
+        Ok(nodes)
+    }
+
+    /// Like get_results, but also returns the hashes attached to each result with add_result_for_hash
+    pub fn get_results_with_hashes(
+        &self,
+        query: String,
+        limit: usize,
+    ) -> ExternResult<Vec<(String, Vec<AnyLinkableHash>)>> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let mut cursor = self.make_cursor(query, &SearchOptions::new(limit))?;
+        let results = self.walk(&mut cursor, limit, None)?;
+        let paths: Vec<TypedPath> = results
+            .iter()
+            .map(|result| result.path.clone().into_typed(self.link_type))
+            .collect();
+        let target_links = get_result_target_links_batch(&paths)?;
+
+        let mut results_with_hashes = vec![];
+        for (result, links) in results.into_iter().zip(target_links) {
+            if let Some(label) = node_label(&result) {
+                // Several agents may have attached the same hash
+                let mut attached: HashSet<AnyLinkableHash> = HashSet::new();
+                let hashes: Vec<AnyLinkableHash> = links
+                    .into_iter()
+                    .map(|link| link.target)
+                    .filter(|hash| attached.insert(hash.clone()))
+                    .collect();
+
+                results_with_hashes.push((label, hashes));
+            }
+        }
+
+        Ok(results_with_hashes)
+    }
+
+    /// Like get_results, but returns a cursor to continue the search from where this page left off
+    ///
+    /// The query is ignored when a cursor is given, since the cursor already holds the position in the index.
+    pub fn get_results_page(
+        &self,
+        query: String,
+        limit: usize,
+        cursor: Option<Cursor>,
+    ) -> ExternResult<Page> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let mut cursor = match cursor {
+            Some(cursor) => cursor,
+            None => self.make_cursor(query, &SearchOptions::new(limit))?,
+        };
+
+        let results = self.walk(&mut cursor, limit, None)?;
+        let next_cursor = match self.has_more(&cursor) {
+            true => Some(cursor),
+            false => None,
+        };
+
+        Ok(Page {
+            results: result_labels(results),
+            next_cursor,
+        })
     }
 
     pub fn get_random_results(&self, limit: usize) -> ExternResult<Vec<String>> {
@@ -159,37 +883,371 @@ impl PrefixIndex {
             )));
         }
 
-        let base_path = Path::from(self.index_name.clone()).typed(self.link_type)?;
+        self.random_results(limit, &mut rand::thread_rng())
+    }
+
+    /// Like get_random_results, but picks the same results given the same `seed`, i.e. derived from the date for "featured tags of the day"
+    ///
+    /// Agents with the same view of the index get the same results for the same seed.
+    pub fn get_random_results_seeded(
+        &self,
+        limit: usize,
+        seed: [u8; 32],
+    ) -> ExternResult<Vec<String>> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        self.random_results(limit, &mut ChaCha20Rng::from_seed(seed))
+    }
+
+    fn random_results(&self, limit: usize, rng: &mut dyn RngCore) -> ExternResult<Vec<String>> {
+        let mut cursor = Cursor::new(self.index_path(), DEFAULT_MIN_DEPTH);
+        let results = self.walk(&mut cursor, limit, Some(rng))?;
 
-        self.inner_get_results(base_path, limit, true)
+        Ok(result_labels(results))
     }
 
-    /// Make a Path to the result following the ShardStrategy specified by PrefixIndex width + depth
-    pub fn make_result_path(&self, text: String, full_text: Option<String>) -> ExternResult<Path> {
-        let mut path_components = Path::from(format!(
-            "{}.{}:{}#{}",
-            self.index_name,
-            self.width,
-            self.depth,
-            text.to_lowercase()
-        ))
-        .as_ref()
-        .clone();
-
-        match full_text {
-            // Replace last component of path with full_text
-            Some(full_text_string) => {
-                path_components.pop();
-                path_components.push(Component::from(full_text_string));
+    /// Get results picked uniformly at random, unlike get_random_results which favours results in sparse branches of the index
+    ///
+    /// Up to MAX_SAMPLED_RESULTS results are visited in a random walk, then `limit` of them are picked with equal probability.
+    /// On indexes with at most MAX_SAMPLED_RESULTS results, every result is equally likely to be picked,
+    /// and so is every set of `limit` results. On larger indexes, they are picked among the first MAX_SAMPLED_RESULTS visited.
+    pub fn get_uniform_random_results(&self, limit: usize) -> ExternResult<Vec<String>> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut cursor = Cursor::new(self.index_path(), DEFAULT_MIN_DEPTH);
+        let mut results = self.walk(&mut cursor, MAX_SAMPLED_RESULTS, Some(&mut rng))?;
+
+        results.shuffle(&mut rng);
+        results.truncate(limit);
+
+        Ok(result_labels(results))
+    }
+
+    /// Like get_random_results, but only results starting with `query`, i.e. random tags starting with "hol"
+    pub fn get_random_results_for_prefix(
+        &self,
+        query: String,
+        limit: usize,
+    ) -> ExternResult<Vec<String>> {
+        if limit == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "limit must be > 0".into()
+            )));
+        }
+
+        let mut rng = rand::thread_rng();
+        let key = self.index_key(&query);
+        let mut children = self.prefix_children(&key)?;
+        children.shuffle(&mut rng);
+
+        // Start from the query's shards, and never climb above them
+        let mut cursor = match children.first() {
+            Some(child) => Cursor::new(child.path.clone(), shard_level(&child.path)),
+            None => return Ok(vec![]),
+        };
+        cursor.pending = children;
+        cursor.strict_key = Some(key);
+
+        let results = self.walk(&mut cursor, limit, Some(&mut rng))?;
+
+        Ok(result_labels(results))
+    }
+
+    /// Walk the index for up to `options.limit` results, in the order `options.ordering` asks for
+    fn find_results(&self, query: String, options: &SearchOptions) -> ExternResult<Vec<Node>> {
+        let mut cursor = self.make_cursor(query, options)?;
+        if options.ordering == Ordering::Relevance {
+            return self.walk(&mut cursor, options.limit, None);
+        }
+
+        let mut results = self.walk(&mut cursor, usize::MAX, None)?;
+        match options.ordering {
+            Ordering::Relevance => {}
+            Ordering::Alphabetical => {
+                sort_by_collation(&mut results, |node| node_label(node).unwrap_or_default())
             }
+            Ordering::Newest => results.sort_by(|a, b| {
+                b.timestamp
+                    .cmp(&a.timestamp)
+                    .then(node_label(a).cmp(&node_label(b)))
+            }),
+            Ordering::Oldest => results.sort_by(|a, b| {
+                a.timestamp
+                    .cmp(&b.timestamp)
+                    .then(node_label(a).cmp(&node_label(b)))
+            }),
+            Ordering::Popularity => results.sort_by(|a, b| {
+                b.popularity
+                    .cmp(&a.popularity)
+                    .then(node_label(a).cmp(&node_label(b)))
+            }),
+        }
+        results.truncate(options.limit);
 
-            // Replace last component of path with original text (preserve case)
-            None => {
-                path_components.pop();
-                path_components.push(Component::from(text));
+        Ok(results)
+    }
+
+    /// Make a Cursor at the start of a search for `query`
+    fn make_cursor(&self, query: String, options: &SearchOptions) -> ExternResult<Cursor> {
+        let path = self.make_result_path(query.clone(), None)?;
+
+        debug!(
+            "Searching for '{:?}', starting at path '{:?}'",
+            query,
+            path_to_string(path.clone().into_typed(self.link_type))
+        );
+
+        match options.match_mode {
+            MatchMode::Fuzzy => Ok(Cursor::new(path, options.min_depth)),
+            MatchMode::Strict => {
+                let key = self.index_key(&query);
+
+                // Results can only continue the query's last shard if it is partial, so never climb above it
+                let mut cursor = Cursor::new(path, options.min_depth.max(self.full_shards(&key)));
+                cursor.strict_key = Some(key);
+
+                Ok(cursor)
+            }
+        }
+    }
+
+    /// Number of shards of `key` that are not cut short by its end
+    fn full_shards(&self, key: &str) -> usize {
+        (self.shard_unit.count(key) / self.width.max(1)).min(self.depth)
+    }
+
+    /// Deepest path that every result starting with the index key `key` is below
+    fn strict_root(&self, key: &str) -> Path {
+        let mut path_components = self.index_path().as_ref().clone();
+        path_components.extend(
+            self.make_shard_components(key)
+                .into_iter()
+                .take(self.full_shards(key)),
+        );
+
+        Path::from(path_components)
+    }
+
+    /// The normalization steps of the index, resolving those referring to custom normalizers
+    pub fn normalizer(&self) -> NormalizationPipeline<'_> {
+        NormalizationPipeline {
+            steps: &self.normalization,
+            custom: &self.custom_normalizers,
+        }
+    }
+
+    /// Text used to build the shards of a result, the result itself keeps the original text
+    fn index_key(&self, text: &str) -> String {
+        convert_case(
+            &self.normalizer().normalize(text),
+            self.case_mode,
+            self.locale.as_deref(),
+        )
+    }
+
+    /// Index keys `text` is added under, its own first
+    fn index_keys(&self, text: &str) -> Vec<String> {
+        let key = self.index_key(text);
+        let mut keys = vec![key.clone()];
+
+        match self.index_mode {
+            IndexMode::Prefix => {}
+            IndexMode::Suffix { min_suffix_len } => {
+                let units = self.shard_unit.split(&key);
+                let min_len = min_suffix_len.max(self.width);
+                for start in 1..units.len() {
+                    if units.len() - start < min_len {
+                        break;
+                    }
+                    keys.push(units[start..].concat());
+                }
+            }
+            IndexMode::NGram { n } => {
+                for gram in self.ngrams(&key, n).into_iter() {
+                    if !keys.contains(&gram) {
+                        keys.push(gram);
+                    }
+                }
+            }
+        }
+
+        keys
+    }
+
+    /// Distinct runs of `n` shard units in `key`, or of the index width if it is larger
+    fn ngrams(&self, key: &str, n: usize) -> Vec<String> {
+        let units = self.shard_unit.split(key);
+
+        let mut grams: Vec<String> = vec![];
+        for gram in units.windows(n.max(self.width)).map(|w| w.concat()) {
+            if !grams.contains(&gram) {
+                grams.push(gram);
             }
         }
-        Ok(Path::from(path_components))
+
+        grams
+    }
+
+    /// Distinct index keys a result with this label can be found under, including those of its words when it is a document
+    fn label_keys(&self, label: &str) -> Vec<String> {
+        let mut distinct = HashSet::new();
+
+        std::iter::once(label.to_string())
+            .chain(self.document_words(label))
+            .flat_map(|text| self.index_keys(&text))
+            .filter(|key| distinct.insert(key.clone()))
+            .collect()
+    }
+
+    /// Words of `text` that add_document indexes it under
+    ///
+    /// Stop words are left out, along with words shorter than the index width,
+    /// since their shard would not pass validation.
+    fn document_words(&self, text: &str) -> Vec<String> {
+        let stop_words: Vec<String> = self
+            .tokenizer
+            .stop_words
+            .iter()
+            .map(|stop_word| self.index_key(stop_word))
+            .collect();
+
+        let mut keys: Vec<String> = vec![];
+        let mut words: Vec<String> = vec![];
+        for word in self.tokenizer.words(text).into_iter() {
+            let key = self.index_key(word);
+            if self.shard_unit.count(&key) < self.width
+                || stop_words.contains(&key)
+                || keys.contains(&key)
+            {
+                continue;
+            }
+
+            keys.push(key);
+            words.push(word.to_string());
+        }
+
+        words
+    }
+
+    /// Whether the text indexed at the leaf `path` starts with `key`
+    fn is_strict_match(&self, path: &Path, key: &str) -> bool {
+        let components = path.as_ref();
+        if components.len() < 2 {
+            return false;
+        }
+
+        let shard_text: String = components[1..components.len() - 1]
+            .iter()
+            .filter_map(|c| String::try_from(c).ok())
+            .collect();
+        if shard_text.starts_with(key) {
+            return true;
+        }
+
+        // Shards only hold the first width * depth characters, the rest can only be compared with the result itself
+        let is_saturated = self.shard_unit.count(&shard_text) >= self.width * self.depth;
+        let maybe_label = components.last().and_then(|c| String::try_from(c).ok());
+
+        match maybe_label {
+            Some(label) => {
+                is_saturated && key.starts_with(&shard_text) && self.is_label_match(&label, key)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether any of the index keys of `label` starts with `key`
+    fn is_label_match(&self, label: &str, key: &str) -> bool {
+        self.label_keys(label)
+            .iter()
+            .any(|label_key| label_key.starts_with(key))
+    }
+
+    /// Score of `label` against the query `key`, and the number of leading units they share,
+    /// using whichever of the label's index keys scores best
+    fn score_label(&self, label: &str, key: &[&str]) -> (f64, usize) {
+        self.label_keys(label)
+            .iter()
+            .map(|label_key| {
+                let label_units = self.shard_unit.split(label_key);
+                let matched = key
+                    .iter()
+                    .zip(label_units.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                // Never outweighs a single shared unit, so longer results only lose ties
+                let extra = label_units.len().saturating_sub(key.len()) as f64;
+                let mut score = matched as f64 - extra / (extra + 1.0);
+                if label_units == key {
+                    score += EXACT_MATCH_BOOST;
+                }
+
+                (score, matched)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or((0.0, 0))
+    }
+
+    /// Fewest edits turning `key` into a prefix of any of the index keys of `label`
+    fn label_edit_distance(&self, label: &str, key: &[&str]) -> usize {
+        self.label_keys(label)
+            .iter()
+            .map(|label_key| prefix_edit_distance(key, &self.shard_unit.split(label_key)))
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Make a Path to the result following the ShardStrategy specified by PrefixIndex width + depth
+    ///
+    /// The components are built directly rather than parsed from a string,
+    /// so the text may contain Path syntax characters such as '.', ':' and '#'.
+    pub fn make_result_path(&self, text: String, full_text: Option<String>) -> ExternResult<Path> {
+        self.normalizer().check()?;
+        let key = self.index_key(&text);
+
+        // Last component is the result itself: either the full_text, or the original text (preserve case)
+        Ok(self.make_key_path(&key, full_text.unwrap_or(text)))
+    }
+
+    /// Make a Path to `label` through the shards of an index key
+    fn make_key_path(&self, key: &str, label: String) -> Path {
+        let mut path_components = self.index_path().as_ref().clone();
+        path_components.extend(self.make_shard_components(key));
+        path_components.push(Component::from(label));
+
+        Path::from(path_components)
+    }
+
+    /// Path to the top of the index, which all results descend from
+    fn index_path(&self) -> Path {
+        Path::from(self.index_name.clone())
+    }
+
+    /// Split `key` into components of `width` shard units, up to `depth` components
+    ///
+    /// With ShardUnit::Char this is the same as holochain's ShardStrategy, so indexes built before remain readable.
+    fn make_shard_components(&self, key: &str) -> Vec<Component> {
+        let units: Vec<&str> = self
+            .shard_unit
+            .split(key)
+            .into_iter()
+            .take(self.width * self.depth)
+            .collect();
+
+        units
+            .chunks(self.width.max(1))
+            .map(|chunk| Component::from(chunk.concat()))
+            .collect()
     }
 
     pub fn validate_create_link(self, action: CreateLink) -> ExternResult<ValidateCallbackResult> {
@@ -216,105 +1274,365 @@ impl PrefixIndex {
         )
     }
 
-    /// Gets the deepest-most Paths that descend from `path`, or it's parents, up to limit
-    fn get_results_from_path(
+    /// Visits the paths pending in `cursor` level by level, collecting up to `limit` leaves
+    ///
+    /// The children of all the pending paths are fetched with a single get_links host call,
+    /// stopping at the path that may complete the results and leaving the rest pending.
+    /// Paths below the deepest shard level are always results, so their children are never fetched.
+    /// Once there are no more pending paths, the siblings of the closest ancestor not yet climbed are queued,
+    /// so the results sharing the longest prefix with the search path come first.
+    /// Children are visited in a random order when given `rng`.
+    fn walk(
         &self,
-        path: TypedPath,
+        cursor: &mut Cursor,
         limit: usize,
-        shuffle: bool,
-    ) -> ExternResult<Vec<TypedPath>> {
-        self.inner_get_results_from_path(path, limit, shuffle, vec![], vec![])
+        mut rng: Option<&mut (dyn RngCore + '_)>,
+    ) -> ExternResult<Vec<Node>> {
+        // Results are checked against their index keys, i.e. to tell them from shards
+        self.normalizer().check()?;
+
+        let mut results: Vec<Node> = vec![];
+
+        while results.len() < limit {
+            if cursor.pending.is_empty() {
+                match self.climb(cursor, rng.as_deref_mut())? {
+                    true => continue,
+                    false => break,
+                }
+            }
+
+            // Pending paths are popped from the back, so the next one is last
+            let mut level = std::mem::take(&mut cursor.pending);
+            level.reverse();
+
+            let wanted = limit - results.len();
+            let mut leaves = 0;
+            let end = level
+                .iter()
+                .position(|node| {
+                    leaves += usize::from(self.is_below_shards(&node.path));
+                    leaves >= wanted
+                })
+                .map_or(level.len(), |i| i + 1);
+            let mut rest = level.split_off(end);
+
+            let to_expand: Vec<TypedPath> = level
+                .iter()
+                .filter(|node| !self.is_below_shards(&node.path))
+                .map(|node| node.path.clone().into_typed(self.link_type))
+                .collect();
+            let mut expanded = get_child_nodes_batch(&to_expand)?.into_iter();
+
+            let mut unvisited = vec![];
+            let mut next_level = vec![];
+            for node in level.into_iter() {
+                let mut children = match self.is_below_shards(&node.path) {
+                    true => vec![],
+                    false => expanded.next().unwrap_or_default(),
+                };
+
+                if !children.is_empty() {
+                    if let Some(rng) = rng.as_deref_mut() {
+                        children.shuffle(rng);
+                    }
+                    next_level.extend(children);
+                } else if results.len() < limit {
+                    results.extend(self.visit_leaf(cursor, node)?);
+                } else {
+                    // Results shallower than the deepest shard level may outnumber limit
+                    unvisited.push(node);
+                }
+            }
+
+            unvisited.append(&mut rest);
+            unvisited.append(&mut next_level);
+            unvisited.reverse();
+            cursor.pending = unvisited;
+        }
+
+        Ok(results)
     }
 
-    fn inner_get_results(
-        &self,
-        path: TypedPath,
-        limit: usize,
-        shuffle: bool,
-    ) -> ExternResult<Vec<String>> {
-        let results = self.get_results_from_path(path, limit, shuffle)?;
+    /// Check a path without children against `cursor`, returning it if it is a result not returned before
+    fn visit_leaf(&self, cursor: &mut Cursor, node: Node) -> ExternResult<Option<Node>> {
+        let is_match = match &cursor.strict_key {
+            Some(key) => self.is_strict_match(&node.path, key),
+            None => true,
+        };
 
-        let leaf_strings: Vec<String> = results
-            .into_iter()
-            .filter(|r| r.leaf().is_some())
-            .map(|p| p.leaf().unwrap().clone())
-            .filter_map(|c| String::try_from(&c).ok())
+        let Some(label) = node_label(&node) else {
+            return Ok(None);
+        };
+        if !is_match || !self.is_result_path(&node.path) {
+            return Ok(None);
+        }
+
+        // A result may be found at several paths, i.e. a document under each of its words
+        let is_multi_path = self.label_keys(&label).len() > 1;
+        if is_multi_path && cursor.seen_labels.contains(&label) {
+            return Ok(None);
+        }
+
+        // Paths reached through a link exist, the search path itself is looked up among its siblings
+        let maybe_node = match node.timestamp {
+            Some(_) => Some(node),
+            None => {
+                let siblings = self.get_siblings(&node.path)?;
+                let maybe_node = siblings
+                    .iter()
+                    .find(|sibling| sibling.path == node.path)
+                    .cloned();
+                if node.path == cursor.climbed {
+                    cursor.climbed_siblings = Some(siblings);
+                }
+
+                maybe_node
+            }
+        };
+        if maybe_node.is_some() && is_multi_path {
+            cursor.seen_labels.insert(label);
+        }
+
+        Ok(maybe_node)
+    }
+
+    /// Whether the leaf of `path`, which has no children, is the label of a result
+    ///
+    /// Shards that other agents linked are left in place when the results below them are removed,
+    /// so a path without children may also be an empty shard.
+    /// Its leaf is a label when the shards above it can't go on: the last one is partial or on the deepest level,
+    /// or when it can't be a shard: it is longer than the width or isn't normalized.
+    /// Otherwise, i.e. "sup.erd.upe", the leaf is only a label if the shards above it are those of its own text.
+    fn is_result_path(&self, path: &Path) -> bool {
+        let components: Vec<String> = path
+            .as_ref()
+            .iter()
+            .filter_map(|c| String::try_from(c).ok())
             .collect();
+        let [_, shards @ .., leaf] = components.as_slice() else {
+            return false;
+        };
+        let Some(last_shard) = shards.last() else {
+            return false;
+        };
+
+        if self.shard_unit.count(last_shard) < self.width
+            || shards.len() >= self.depth
+            || !self.is_shard(leaf)
+        {
+            return true;
+        }
 
-        Ok(leaf_strings)
+        self.label_keys(leaf).iter().any(|key| {
+            self.make_shard_components(key)
+                .iter()
+                .filter_map(|c| String::try_from(c).ok())
+                .eq(shards.iter().cloned())
+        })
+    }
+
+    /// Whether `component` could be a shard of this index, i.e. normalized text of at most width shard units
+    fn is_shard(&self, component: &str) -> bool {
+        let units = self.shard_unit.count(component);
+
+        units > 0
+            && units <= self.width
+            && self.normalizer().is_normalized(component)
+            && convert_case(component, self.case_mode, self.locale.as_deref()) == component
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn inner_get_results_from_path(
+    /// Whether `path` is below the deepest shard level, where there is nothing but results
+    fn is_below_shards(&self, path: &Path) -> bool {
+        shard_level(path) > self.depth
+    }
+
+    /// Queues the siblings of the deepest ancestor climbed so far, returns false if there is nothing left to climb
+    fn climb(
         &self,
-        path: TypedPath,
-        limit: usize,
-        shuffle: bool,
-        mut visited: Vec<TypedPath>,
-        mut results: Vec<TypedPath>,
-    ) -> ExternResult<Vec<TypedPath>> {
-        visited.push(path.clone());
+        cursor: &mut Cursor,
+        rng: Option<&mut (dyn RngCore + '_)>,
+    ) -> ExternResult<bool> {
+        let climbed = cursor.climbed.clone().into_typed(self.link_type);
+
+        match climbed.parent() {
+            Some(parent) if shard_level(&parent) >= cursor.min_depth => {
+                let siblings = match cursor.climbed_siblings.take() {
+                    Some(siblings) => siblings,
+                    None => get_child_nodes(parent.clone())?,
+                };
+                let mut siblings: Vec<Node> = siblings
+                    .into_iter()
+                    .filter(|sibling| sibling.path != climbed.path)
+                    .collect();
 
-        let mut children = get_children_paths(path.clone())?;
-        match children.is_empty() {
-            true => {
-                if path.exists()? && !results.contains(&path) && results.len() < limit {
-                    results.push(path.clone());
+                if let Some(rng) = rng {
+                    siblings.shuffle(rng);
                 }
 
-                match path.parent() {
-                    Some(parent) => {
-                        if !visited.contains(&parent) && !parent.is_root() {
-                            return self.inner_get_results_from_path(
-                                parent, limit, shuffle, visited, results,
-                            );
-                        }
+                cursor.pending.extend(siblings.into_iter().rev());
+                cursor.climbed = parent.path;
 
-                        Ok(results)
-                    }
-                    None => Ok(results),
-                }
+                Ok(true)
             }
-            false => {
-                if shuffle {
-                    let mut rng = rand::thread_rng();
-                    children.shuffle(&mut rng)
-                }
+            _ => Ok(false),
+        }
+    }
 
-                for child in children.into_iter() {
-                    let grandchildren = self
-                        .inner_get_results_from_path(
-                            child.clone(),
-                            limit,
-                            shuffle,
-                            visited.clone(),
-                            results.clone(),
-                        )
-                        .unwrap_or_default();
-
-                    for grandchild in grandchildren.into_iter() {
-                        if grandchild.exists()?
-                            && !results.contains(&grandchild)
-                            && results.len() < limit
-                        {
-                            results.push(grandchild.clone());
-                        }
-                    }
-                }
+    /// Children of the deepest path the full shards of `key` lead to, that may lead to results starting with `key`
+    fn prefix_children(&self, key: &str) -> ExternResult<Vec<Node>> {
+        self.normalizer().check()?;
 
-                match path.parent() {
-                    Some(parent) => {
-                        if !visited.contains(&parent) && !parent.is_root() {
-                            return self.inner_get_results_from_path(
-                                parent, limit, shuffle, visited, results,
-                            );
-                        }
+        let units = self.shard_unit.split(key);
+        let full_shards = (units.len() / self.width.max(1)).min(self.depth);
+        let rest: String = units[full_shards * self.width..].concat();
 
-                        Ok(results)
-                    }
-                    None => Ok(results),
+        let mut path_components = self.index_path().as_ref().clone();
+        path_components.extend(
+            self.make_shard_components(key)
+                .into_iter()
+                .take(full_shards),
+        );
+        let path = Path::from(path_components).into_typed(self.link_type);
+
+        Ok(get_child_nodes(path)?
+            .into_iter()
+            .filter(|child| {
+                // A child is either the next shard, or the label of a result
+                let component = node_label(child).unwrap_or_default();
+                (full_shards < self.depth && component.starts_with(&rest))
+                    || self.is_label_match(&component, key)
+            })
+            .collect())
+    }
+
+    /// Estimate the number of leaves below `path` by following random children down to one
+    fn probe_leaf_count(&self, path: Path) -> ExternResult<f64> {
+        let mut rng = rand::thread_rng();
+        let mut estimate = 1.0;
+        let mut path = path;
+
+        loop {
+            let children = get_child_nodes(path.into_typed(self.link_type))?;
+            match children.choose(&mut rng) {
+                Some(child) => {
+                    estimate *= children.len() as f64;
+                    path = child.path.clone();
                 }
+                None => return Ok(estimate),
+            }
+        }
+    }
+
+    /// Children of the parent of `path`, including `path` itself if it is in the index
+    fn get_siblings(&self, path: &Path) -> ExternResult<Vec<Node>> {
+        match path.clone().into_typed(self.link_type).parent() {
+            Some(parent) => get_child_nodes(parent),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Whether walking `cursor` could still yield results
+    fn has_more(&self, cursor: &Cursor) -> bool {
+        if !cursor.pending.is_empty() {
+            return true;
+        }
+
+        let climbed = cursor.climbed.clone().into_typed(self.link_type);
+        match climbed.parent() {
+            Some(parent) => shard_level(&parent) >= cursor.min_depth,
+            None => false,
+        }
+    }
+}
+
+/// A path waiting to be expanded by get_results_fuzzy
+struct FuzzyNode {
+    path: Path,
+    /// Last row of the Levenshtein matrix between the query and the shards down to this path
+    row: Vec<usize>,
+    /// Fewest edits turning the query into any prefix of the shards down to this path
+    distance: usize,
+    /// Fewest edits turning the query into a prefix of this path's component, in case it is the label of a result
+    label_distance: usize,
+}
+
+impl FuzzyNode {
+    /// Fewest edits any result below this path could need
+    fn min_distance(&self) -> usize {
+        let row_min = self.row.iter().min().copied().unwrap_or(usize::MAX);
+        self.distance.min(row_min).min(self.label_distance)
+    }
+}
+
+/// State of a get_results_fuzzy search
+struct FuzzySearch {
+    limit: usize,
+    /// Paths waiting to be expanded, by the fewest edits a result below them could need, then in the order they were found
+    pending: BTreeMap<(usize, usize), FuzzyNode>,
+    /// Number of paths pushed so far, to keep paths needing as many edits in the order they were found
+    pushed: usize,
+    /// Closest results found so far, by distance, then label
+    results: BTreeSet<(usize, String)>,
+    /// Labels of the results found so far, since a result may be found at several paths
+    labels: HashSet<String>,
+}
+
+impl FuzzySearch {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            pending: BTreeMap::new(),
+            pushed: 0,
+            results: BTreeSet::new(),
+            labels: HashSet::new(),
+        }
+    }
+
+    fn push(&mut self, node: FuzzyNode) {
+        self.pending
+            .insert((node.min_distance(), self.pushed), node);
+        self.pushed += 1;
+    }
+
+    /// The pending paths needing the fewest edits, to expand them together
+    fn pop_closest(&mut self) -> Vec<FuzzyNode> {
+        let mut closest: Vec<FuzzyNode> = vec![];
+        while let Some(entry) = self.pending.first_entry() {
+            if closest
+                .first()
+                .is_some_and(|node| entry.key().0 > node.min_distance())
+            {
+                break;
+            }
+            closest.push(entry.remove());
+        }
+
+        closest
+    }
+
+    fn add_result(&mut self, distance: usize, label: String) {
+        if self.labels.insert(label.clone()) {
+            self.results.insert((distance, label));
+            if self.results.len() > self.limit {
+                self.results.pop_last();
             }
         }
     }
+
+    /// Whether no path needing at least `min_distance` edits could lead to a closer result than those found so far
+    fn is_complete(&self, min_distance: usize) -> bool {
+        self.results.len() >= self.limit
+            && self
+                .results
+                .last()
+                .is_some_and(|(distance, _)| *distance < min_distance)
+    }
+}
+
+fn result_labels(results: Vec<Node>) -> Vec<String> {
+    results.iter().filter_map(node_label).collect()
+}
+
+fn node_label(node: &Node) -> Option<String> {
+    node.path.leaf().and_then(|c| String::try_from(c).ok())
 }
@@ -0,0 +1,39 @@
+use hdk::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Where a document is split into words
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WordBoundary {
+    /// Unicode word boundaries (UAX #29), leaving out whitespace and punctuation
+    #[default]
+    Unicode,
+    /// Whitespace only, so punctuation stays part of the words, i.e. "#hashtag" or "C++"
+    Whitespace,
+}
+
+/// Splits documents into the words they are indexed under
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Tokenizer {
+    #[serde(default)]
+    pub word_boundary: WordBoundary,
+    /// Words that are never indexed, i.e. "the" or "of", compared once normalized like the rest of the index
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+}
+
+impl Tokenizer {
+    pub fn new(word_boundary: WordBoundary, stop_words: Vec<String>) -> Self {
+        Self {
+            word_boundary,
+            stop_words,
+        }
+    }
+
+    /// Split `text` into words, stop words included
+    pub fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self.word_boundary {
+            WordBoundary::Unicode => text.unicode_words().collect(),
+            WordBoundary::Whitespace => text.split_whitespace().collect(),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What the width of a PrefixIndex counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ShardUnit {
+    /// Unicode scalar values, same as holochain's ShardStrategy
+    #[default]
+    Char,
+    /// Extended grapheme clusters, so emoji with modifiers and letters with combining marks are never split across shards
+    Grapheme,
+}
+
+impl ShardUnit {
+    /// Split `text` into units
+    pub fn split(self, text: &str) -> Vec<&str> {
+        match self {
+            ShardUnit::Char => text
+                .char_indices()
+                .map(|(i, c)| &text[i..i + c.len_utf8()])
+                .collect(),
+            ShardUnit::Grapheme => text.graphemes(true).collect(),
+        }
+    }
+
+    /// Number of units in `text`
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            ShardUnit::Char => text.chars().count(),
+            ShardUnit::Grapheme => text.graphemes(true).count(),
+        }
+    }
+}
+
+/// Which texts a result is indexed under
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum IndexMode {
+    /// The result's text only, so it is found by its prefixes
+    #[default]
+    Prefix,
+    /// Every suffix of the result's text that is at least `min_suffix_len` shard units long,
+    /// so it is also found by any part of it, i.e. "comp" finds "supercomputing"
+    ///
+    /// Suffixes shorter than the index width are never indexed.
+    /// Each suffix adds its own path to the index, so long results create many more links.
+    Suffix { min_suffix_len: usize },
+    /// The result's text and every run of `n` shard units within it,
+    /// so it is found by fragments of it in any order with get_results_ngram, i.e. "ochai" finds "holochain"
+    ///
+    /// N-grams shorter than the index width can't be indexed, so `n` is at least the width.
+    NGram { n: usize },
+}
+
+/// By default searches never climb up to the index name, so results share at least the first shard with the query
+pub const DEFAULT_MIN_DEPTH: usize = 1;
+
+/// Most get_links calls a fuzzy search may make, since typos can send it down many branches of the index
+pub const MAX_FUZZY_GET_LINKS_CALLS: usize = 100;
+
+/// Most results get_uniform_random_results visits to pick from
+pub const MAX_SAMPLED_RESULTS: usize = 1000;
+
+/// Random branches followed down the index by estimate_count, below the levels it counts exactly
+pub const ESTIMATE_COUNT_PROBES: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
+pub struct SearchOptions {
+    pub limit: usize,
+    /// Shallowest shard level the search may climb up to when it runs out of results below the query
+    ///
+    /// 0 searches the whole index, 1 only results sharing the first shard with the query, etc.
+    /// Setting it to the number of shards in the query (i.e. its length divided by the index width, up to its depth)
+    /// only returns results below the query's deepest shard.
+    #[serde(default = "default_min_depth")]
+    pub min_depth: usize,
+    #[serde(default)]
+    pub match_mode: MatchMode,
+    #[serde(default)]
+    pub ordering: Ordering,
+}
+
+impl SearchOptions {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            min_depth: DEFAULT_MIN_DEPTH,
+            match_mode: MatchMode::default(),
+            ordering: Ordering::default(),
+        }
+    }
+}
+
+fn default_min_depth() -> usize {
+    DEFAULT_MIN_DEPTH
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MatchMode {
+    /// Results similar to the query, i.e. sharing its shards as far down the index as possible
+    #[default]
+    Fuzzy,
+    /// Only results starting with the query, including the characters beyond the index's width * depth
+    Strict,
+}
+
+/// Position in a PrefixIndex search, used to continue the search where the previous page left off
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
+pub struct Cursor {
+    /// Paths still to be visited, the next one last
+    pub(crate) pending: Vec<Node>,
+    /// Deepest ancestor of the search path whose siblings have already been queued
+    pub(crate) climbed: Path,
+    /// Shallowest shard level the search may climb up to
+    pub(crate) min_depth: usize,
+    /// Index key that results must start with, when searching with MatchMode::Strict
+    #[serde(default)]
+    pub(crate) strict_key: Option<String>,
+    /// Results already returned that are indexed at several paths, so they are returned only once
+    ///
+    /// Results indexed at a single path can't be found again, so they are not kept and the cursor stays small.
+    #[serde(default)]
+    pub(crate) seen_labels: BTreeSet<String>,
+    /// Children of the parent of `climbed`, when they were fetched to look up the search path itself,
+    /// so the first climb doesn't fetch them again
+    #[serde(skip)]
+    pub(crate) climbed_siblings: Option<Vec<Node>>,
+}
+
+impl Cursor {
+    pub(crate) fn new(path: Path, min_depth: usize) -> Self {
+        Self {
+            pending: vec![Node {
+                path: path.clone(),
+                timestamp: None,
+                popularity: 0,
+            }],
+            climbed: path,
+            min_depth,
+            strict_key: None,
+            seen_labels: BTreeSet::new(),
+            climbed_siblings: None,
+        }
+    }
+}
+
+/// A path in the index, along with what the links to it tell about it
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct Node {
+    pub(crate) path: Path,
+    /// Creation time of the newest link to the path, None until those links are fetched
+    #[serde(default)]
+    pub(crate) timestamp: Option<Timestamp>,
+    /// Number of distinct agents linking to the path
+    #[serde(default)]
+    pub(crate) popularity: usize,
+}
+
+/// Order of the results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Ordering {
+    /// Closest to the query first, in the order the index is walked
+    #[default]
+    Relevance,
+    /// Alphabetically by label, following the Unicode Collation Algorithm
+    ///
+    /// Every result within reach of the search is fetched to sort them,
+    /// so narrow the search down with min_depth or MatchMode::Strict on large indexes.
+    Alphabetical,
+    /// Most recently added first, by the creation time of the newest link to each result
+    Newest,
+    /// Least recently added first, by the creation time of the newest link to each result
+    Oldest,
+    /// Added by the most distinct agents first
+    Popularity,
+}
+
+/// A page of results, along with the cursor to get the next page
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
+pub struct Page {
+    pub results: Vec<String>,
+    /// None if there are no more results
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Host calls a search made, to measure what it costs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, SerializedBytes)]
+pub struct SearchStats {
+    /// Number of get_links host calls, each fetching the links of one or more paths
+    pub get_links_calls: usize,
+    /// Number of paths whose links were fetched
+    pub paths_fetched: usize,
+}
+
+/// A result ranked by how well it matches the search query
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
+pub struct SearchResult {
+    pub label: String,
+    /// Shard units shared with the query, plus a boost for an exact match, minus a penalty under 1 for extra length
+    pub score: f64,
+    /// Number of leading shard units the result shares with the query
+    pub matched_prefix_len: usize,
+    /// Components of the path the result was found at, from the index name down to the label
+    pub path: Vec<String>,
+    /// When the result was last added to the index
+    pub timestamp: Timestamp,
+    /// Number of distinct agents who added the result
+    pub popularity: usize,
+}
+
+/// A component right below a prefix of the index, to drill down into it
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, SerializedBytes)]
+pub struct BrowseNode {
+    /// A shard, or the label of a result
+    pub component: String,
+    /// Prefix to browse to drill down into this node, i.e. "superd" for the shard "erd" below "sup", or the label of a result
+    pub full_prefix: String,
+    /// Whether the node is a result, with nothing to drill down into
+    pub is_leaf: bool,
+    /// Number of components right below the node
+    pub child_count: usize,
+}
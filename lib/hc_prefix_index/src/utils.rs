@@ -1,15 +1,59 @@
+use crate::types::Node;
+use ::hdk::hdk::HDK;
 use hdk::prelude::*;
+use std::cell::Cell;
+use std::collections::HashSet;
+
+/// Tag of the links that attach a target hash to the leaf path of a result
+///
+/// Path links are tagged with a msgpack encoded Component, which never starts with these bytes,
+/// so both kinds of links can share the index's link type.
+pub const RESULT_TARGET_LINK_TAG: &[u8] = b"hc_prefix_index.target";
+
+pub fn is_result_target_link(link: &Link) -> bool {
+    link.tag.0 == RESULT_TARGET_LINK_TAG
+}
+
+thread_local! {
+    /// get_links host calls made so far, and paths whose links they fetched
+    static GET_LINKS_STATS: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+/// Number of get_links host calls made so far, and of paths whose links they fetched
+pub fn get_links_stats() -> (usize, usize) {
+    GET_LINKS_STATS.with(|stats| stats.get())
+}
+
+/// Fetch the links of several bases with a single get_links host call
+pub fn get_links_batch(inputs: Vec<GetLinksInput>) -> ExternResult<Vec<Vec<Link>>> {
+    if inputs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    GET_LINKS_STATS.with(|stats| {
+        let (calls, paths) = stats.get();
+        stats.set((calls + 1, paths + inputs.len()));
+    });
+    HDK.with(|h| h.borrow().get_links(inputs))
+}
+
+/// Input to get the links from `path` to its children
+fn children_input(path: &TypedPath) -> ExternResult<GetLinksInput> {
+    Ok(GetLinksInputBuilder::try_new(
+        path.path_entry_hash()?,
+        LinkTypeFilter::single_type(path.link_type.zome_index, path.link_type.zome_type),
+    )?
+    .build())
+}
 
 /// Duplicates of get_children from holochain TypedPath
 /// but without calling ensure() on those children
 pub fn get_children(path: TypedPath) -> ExternResult<Vec<Link>> {
-    let mut unwrapped = get_links(
-        GetLinksInputBuilder::try_new(
-            path.path_entry_hash()?,
-            LinkTypeFilter::single_type(path.link_type.zome_index, path.link_type.zome_type),
-        )?
-        .build(),
-    )?;
+    let mut unwrapped = get_links_batch(vec![children_input(&path)?])?
+        .pop()
+        .unwrap_or_default();
+    // Target links point out of the index, they are not part of the tree.
+    unwrapped.retain(|link| !is_result_target_link(link));
     // Only need one of each hash to build the tree.
     unwrapped.sort_unstable_by(|a, b| a.tag.cmp(&b.tag));
     unwrapped.dedup_by(|a, b| a.tag.eq(&b.tag));
@@ -22,18 +66,7 @@ pub fn get_children_paths(path: TypedPath) -> ExternResult<Vec<TypedPath>> {
     let children = get_children(path.clone())?;
     let components: ExternResult<Vec<Option<Component>>> = children
         .into_iter()
-        .map(|link| {
-            let component_bytes = &link.tag.0[..];
-            if component_bytes.is_empty() {
-                Ok(None)
-            } else {
-                Ok(Some(
-                    SerializedBytes::from(UnsafeBytes::from(component_bytes.to_vec()))
-                        .try_into()
-                        .map_err(|e: SerializedBytesError| wasm_error!(e))?,
-                ))
-            }
-        })
+        .map(|link| link_tag_component(&link.tag))
         .collect();
     Ok(components?
         .into_iter()
@@ -47,6 +80,140 @@ pub fn get_children_paths(path: TypedPath) -> ExternResult<Vec<TypedPath>> {
         .collect())
 }
 
+/// Like get_children_paths, but also keeps what the links to each child tell about it
+pub fn get_child_nodes(path: TypedPath) -> ExternResult<Vec<Node>> {
+    Ok(get_child_nodes_batch(&[path])?.pop().unwrap_or_default())
+}
+
+/// Like get_child_nodes for each of `paths`, with a single get_links host call
+pub fn get_child_nodes_batch(paths: &[TypedPath]) -> ExternResult<Vec<Vec<Node>>> {
+    let inputs = paths
+        .iter()
+        .map(children_input)
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+
+    get_links_batch(inputs)?
+        .into_iter()
+        .zip(paths.iter())
+        .map(|(links, path)| child_nodes(&path.path, links))
+        .collect()
+}
+
+/// Group the links from `path` by the child they lead to
+fn child_nodes(path: &Path, mut links: Vec<Link>) -> ExternResult<Vec<Node>> {
+    links.retain(|link| !is_result_target_link(link));
+    links.sort_unstable_by(|a, b| a.tag.cmp(&b.tag));
+
+    // Several agents may have linked the same child
+    let mut nodes: Vec<Node> = vec![];
+    let mut authors: HashSet<AgentPubKey> = HashSet::new();
+    let mut last_tag: Option<LinkTag> = None;
+    for link in links.into_iter() {
+        if last_tag.as_ref() == Some(&link.tag) {
+            authors.insert(link.author);
+            if let Some(node) = nodes.last_mut() {
+                node.timestamp = node.timestamp.max(Some(link.timestamp));
+                node.popularity = authors.len();
+            }
+            continue;
+        }
+
+        let mut child_path = path.clone();
+        if let Some(component) = link_tag_component(&link.tag)? {
+            child_path.append_component(component);
+        }
+        nodes.push(Node {
+            path: child_path,
+            timestamp: Some(link.timestamp),
+            popularity: 1,
+        });
+        authors = HashSet::from([link.author]);
+        last_tag = Some(link.tag);
+    }
+
+    Ok(nodes)
+}
+
+/// Tag of the link from the parent of `path` to it, same as TypedPath::ensure
+pub fn path_link_tag(path: &Path) -> ExternResult<LinkTag> {
+    Ok(LinkTag::new(match path.leaf() {
+        None => <Vec<u8>>::with_capacity(0),
+        Some(component) => UnsafeBytes::from(
+            SerializedBytes::try_from(component)
+                .map_err(|e: SerializedBytesError| wasm_error!(e))?,
+        )
+        .into(),
+    }))
+}
+
+/// Decode the Component a path link is tagged with
+fn link_tag_component(tag: &LinkTag) -> ExternResult<Option<Component>> {
+    let component_bytes = &tag.0[..];
+    if component_bytes.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(
+            SerializedBytes::from(UnsafeBytes::from(component_bytes.to_vec()))
+                .try_into()
+                .map_err(|e: SerializedBytesError| wasm_error!(e))?,
+        ))
+    }
+}
+
+/// Get the links from the leaf path of each result to its target hashes, with a single get_links host call
+pub fn get_result_target_links_batch(paths: &[TypedPath]) -> ExternResult<Vec<Vec<Link>>> {
+    let inputs = paths
+        .iter()
+        .map(|path| {
+            Ok(GetLinksInputBuilder::try_new(
+                path.path_entry_hash()?,
+                LinkTypeFilter::single_type(path.link_type.zome_index, path.link_type.zome_type),
+            )?
+            .tag_prefix(LinkTag::new(RESULT_TARGET_LINK_TAG))
+            .build())
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+
+    get_links_batch(inputs)
+}
+
+/// Get the links from the leaf path of a result to its target hashes
+pub fn get_result_target_links(path: TypedPath) -> ExternResult<Vec<Link>> {
+    Ok(get_result_target_links_batch(&[path])?
+        .pop()
+        .unwrap_or_default())
+}
+
+/// Number of components below the index name, i.e. 0 for the index name itself
+pub fn shard_level(path: &Path) -> usize {
+    path.as_ref().len().saturating_sub(1)
+}
+
+/// Extend a row of the Levenshtein matrix between `key` and some text by one more unit of that text
+///
+/// `row[i]` is the number of edits turning the first `i` units of `key` into the text.
+pub fn next_edit_row(key: &[&str], row: &[usize], unit: &str) -> Vec<usize> {
+    let mut next = vec![row[0] + 1];
+    for (i, key_unit) in key.iter().enumerate() {
+        let substitution = row[i] + usize::from(*key_unit != unit);
+        next.push(substitution.min(row[i + 1] + 1).min(next[i] + 1));
+    }
+
+    next
+}
+
+/// Fewest edits turning `key` into any prefix of `text`
+pub fn prefix_edit_distance(key: &[&str], text: &[&str]) -> usize {
+    let mut row: Vec<usize> = (0..=key.len()).collect();
+    let mut distance = row[key.len()];
+    for unit in text.iter() {
+        row = next_edit_row(key, &row, unit);
+        distance = distance.min(row[key.len()]);
+    }
+
+    distance
+}
+
 pub fn path_to_string(path: TypedPath) -> String {
     let component_strings: Vec<String> = path
         .as_ref()
@@ -1,3 +1,5 @@
+use crate::normalize::{convert_case, Normalizer};
+use crate::utils::RESULT_TARGET_LINK_TAG;
 use crate::PrefixIndex;
 use hdi::hash_path::path::root_hash;
 use hdk::prelude::*;
@@ -9,6 +11,17 @@ pub fn validate_create_link_prefix_index(
     tag: LinkTag,
     prefix_index: PrefixIndex,
 ) -> ExternResult<ValidateCallbackResult> {
+    // Result target link: leaf path -> any hash
+    if tag.0 == RESULT_TARGET_LINK_TAG {
+        if base_address == root_hash()? {
+            return Ok(ValidateCallbackResult::Invalid(
+                "PrefixIndex result target: base address cannot be the root hash".into(),
+            ));
+        }
+
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
     let tag_bytes = SerializedBytes::from(UnsafeBytes::from(tag.into_inner()));
     let tag_component = Component::try_from(tag_bytes).map_err(|e| wasm_error!(e))?;
     let tag_string = String::try_from(&tag_component).map_err(|e| wasm_error!(e))?;
@@ -41,10 +54,28 @@ pub fn validate_create_link_prefix_index(
     }
     // second component
     else if let Some(eh) = base_address.into_entry_hash() {
-        if eh == Path::from(prefix_index.index_name.clone()).path_entry_hash()?
-            && tag_string.chars().count() != prefix_index.width
-        {
-            return Ok(ValidateCallbackResult::Invalid("PrefixIndex second component: tag string must have same number of chars as prefix index width".into()));
+        if eh == Path::from(prefix_index.index_name.clone()).path_entry_hash()? {
+            if prefix_index.shard_unit.count(&tag_string) != prefix_index.width {
+                return Ok(ValidateCallbackResult::Invalid("PrefixIndex second component: tag string must have same number of shard units as prefix index width".into()));
+            }
+            let normalizer = prefix_index.normalizer();
+            normalizer.check()?;
+            if !normalizer.is_normalized(&tag_string) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "PrefixIndex second component: tag string must be normalized".into(),
+                ));
+            }
+            // Always passes for case sensitive indexes
+            if convert_case(
+                &tag_string,
+                prefix_index.case_mode,
+                prefix_index.locale.as_deref(),
+            ) != tag_string
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "PrefixIndex second component: tag string must follow the case mode of the prefix index".into(),
+                ));
+            }
         }
     }
     // third or later component